@@ -0,0 +1,177 @@
+//! switches plugs in a dedicated task fed by a channel, so that neither the api nor the scheduler
+//! wait on the network while holding the state lock. a newer command for a plug supersedes
+//! a pending one (including its retries).
+
+use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::retry;
+use crate::plug::{self, Plug, PowerSwitch};
+
+/// status of commands being retried, by plug
+type Statuses = Arc<Mutex<HashMap<PlugId, retry::Status>>>;
+
+/// identifies a plug across enclosures
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlugId {
+    pub enclosure: String,
+    pub name: String,
+}
+
+impl std::fmt::Display for PlugId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of enclosure {}", self.name, self.enclosure)
+    }
+}
+
+struct Command {
+    id: PlugId,
+    plug: Plug,
+    power: bool,
+    mode: Mode,
+}
+
+enum Mode {
+    /// attempt only once and send the result
+    Once(oneshot::Sender<Result<(), plug::Error>>),
+    /// retry on error according to policy
+    Retry(retry::Policy),
+    /// only switch if the plug is not in given power state already, retrying on error according to policy.
+    /// skipped if a command for the plug is still pending, as that one is at least as recent
+    Reconcile(retry::Policy),
+}
+
+/// handle to send commands to the actuator task
+#[derive(Debug, Clone)]
+pub struct Actuator {
+    sender: mpsc::UnboundedSender<Command>,
+    statuses: Statuses,
+}
+
+impl Actuator {
+    /// spawn actuator task, which runs as long as any handle exists
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let statuses = Statuses::default();
+        tokio::spawn(run(receiver, Arc::clone(&statuses)));
+        Self { sender, statuses }
+    }
+
+    /// switch plug in the background, retrying on error according to `retry_policy`
+    pub fn switch(&self, id: PlugId, plug: Plug, retry_policy: retry::Policy, power: bool) {
+        self.send(Command { id, plug, power, mode: Mode::Retry(retry_policy) });
+    }
+
+    /// switch plug in the background if it is not in given power state already, retrying on error according to `retry_policy`.
+    /// ordered with the other commands, so it is superseded by any later one instead of overriding it
+    pub fn reconcile(&self, id: PlugId, plug: Plug, retry_policy: retry::Policy, power: bool) {
+        self.send(Command { id, plug, power, mode: Mode::Reconcile(retry_policy) });
+    }
+
+    /// switch plug with a single attempt. the command is sent right away, so it is ordered before later ones
+    /// even if its result is awaited after releasing a lock.
+    pub fn switch_once(&self, id: PlugId, plug: Plug, power: bool) -> impl Future<Output = Result<(), plug::Error>> + use<> {
+        let (sender, receiver) = oneshot::channel();
+        self.send(Command { id, plug, power, mode: Mode::Once(sender) });
+        // sender is only dropped without a result if the command was superseded in the meantime
+        async move { receiver.await.unwrap_or(Err(plug::Error::Superseded)) }
+    }
+
+    /// status of the command for given plug, if it is currently being retried
+    pub fn retry_status(&self, id: &PlugId) -> Option<retry::Status> {
+        self.statuses.lock().unwrap().get(id).copied()
+    }
+
+    fn send(&self, command: Command) {
+        if self.sender.send(command).is_err() {
+            log::error!("actuator task stopped, can't switch plugs anymore");
+        }
+    }
+}
+
+async fn run(mut receiver: mpsc::UnboundedReceiver<Command>, statuses: Statuses) {
+    // tasks executing the latest command of every plug
+    let mut tasks: HashMap<PlugId, JoinHandle<()>> = HashMap::new();
+
+    while let Some(command) = receiver.recv().await {
+        tasks.retain(|_, task| !task.is_finished());
+
+        let id = command.id;
+        if matches!(command.mode, Mode::Reconcile(_)) && tasks.contains_key(&id) {
+            log::debug!("skipping reconciliation of plug {id}, as a command is pending");
+            continue;
+        }
+        if let Some(task) = tasks.remove(&id) {
+            log::info!("superseding pending command for plug {id}");
+            task.abort();
+        }
+        statuses.lock().unwrap().remove(&id);
+
+        let plug = command.plug;
+        let power = command.power;
+        let task = match command.mode {
+            Mode::Once(response) => tokio::spawn(async move {
+                // receiver might not be interested anymore
+                let _ = response.send(plug.set_power(power).await);
+            }),
+            Mode::Retry(policy) => {
+                let statuses = Arc::clone(&statuses);
+                let id = id.clone();
+                tokio::spawn(async move {
+                    set_power_with_retry(&id, &plug, policy, power, &statuses).await;
+                    statuses.lock().unwrap().remove(&id);
+                })
+            },
+            Mode::Reconcile(policy) => {
+                let statuses = Arc::clone(&statuses);
+                let id = id.clone();
+                tokio::spawn(async move {
+                    match plug.get_power().await {
+                        Ok(actual) if actual == power => log::trace!("plug {id} is in expected state"),
+                        Ok(_) => {
+                            log::info!("plug {id} drifted from expected state, turning it {}", if power { "on" } else { "off" });
+                            set_power_with_retry(&id, &plug, policy, power, &statuses).await;
+                            statuses.lock().unwrap().remove(&id);
+                        },
+                        Err(error) => log::warn!("could not get power state of plug {id} for reconciliation: {error:?}"),
+                    }
+                })
+            },
+        };
+        tasks.insert(id, task);
+    }
+}
+
+async fn set_power_with_retry(id: &PlugId, plug: &Plug, policy: retry::Policy, power: bool, statuses: &Statuses) {
+    let started = tokio::time::Instant::now();
+    let started_at = chrono::Utc::now();
+    if plug.set_power(power).await.is_ok() {
+        return;
+    }
+
+    for retry in 1 ..= policy.max_retries {
+        let interval = policy.interval_with_jitter(retry);
+        if policy.exceeds_deadline(started.elapsed(), interval) {
+            log::warn!("failed to set power state of plug {id} before deadline");
+            return;
+        }
+
+        log::warn!("failed to set power state of plug {id}, attempting retry {retry} in {} seconds", interval.as_secs());
+        statuses.lock().unwrap().insert(id.clone(), retry::Status {
+            power,
+            retries: retry - 1,
+            started_at,
+            next_retry_at: chrono::Utc::now() + interval,
+        });
+        tokio::time::sleep(interval).await;
+
+        if plug.set_power(power).await.is_ok() {
+            log::info!("succeeded to set power state of plug {id} after {retry} retries");
+            return;
+        }
+    }
+
+    log::warn!("failed to set power state of plug {id} after max retries");
+}
@@ -0,0 +1,83 @@
+use axum::{extract, http::StatusCode};
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use crate::time::Time;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper, Automation};
+use crate::api::{WebResponse, bad_request_if, enclosure_mut, parse_timer};
+
+/// kind of automation, see [`Automation`]
+#[derive(Debug, Clone, Copy, Default, utoipa::ToSchema, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationKind {
+    /// Plugs follow their schedules
+    #[default]
+    Active,
+    /// Plugs are not switched automatically
+    Paused,
+    /// All plugs follow `intervals` every day instead of their schedules
+    Vacation,
+}
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutAutomationQuery {
+    /// Whether and how plugs are switched automatically
+    #[serde(default)]
+    #[param(inline)]
+    kind: AutomationKind,
+
+    /// Local date to resume automation on (`YYYY-MM-DD`), for kinds `paused` and `vacation`.
+    /// If missing, automation stays paused or replaced until set to `active` again.
+    #[param(value_type = Option<String>, example = "2025-08-01")]
+    until: Option<NaiveDate>,
+
+    /// Intervals to turn all plugs on/off every day (`HH:MM-HH:MM`, comma-separated), required for kind `vacation`
+    #[param(example = "09:00-19:00")]
+    intervals: Option<String>,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/configuration/automation",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), PutAutomationQuery),
+    responses(
+        (status = 200, description = "Successfully configured automation, keeping the computed timers"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn put_automation(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<PutAutomationQuery>,
+) -> WebResponse<&'static str> {
+    let until = query.until;
+    let automation = match query.kind {
+        AutomationKind::Active => {
+            bad_request_if(until.is_some(), "until is not supported for kind active".to_string())?;
+            Automation::Active
+        },
+        AutomationKind::Paused => Automation::Paused { until },
+        AutomationKind::Vacation => {
+            let Some(intervals) = query.intervals else {
+                return Err((StatusCode::BAD_REQUEST, "intervals is required for kind vacation".to_string()));
+            };
+            Automation::Vacation { timer: parse_timer(&intervals)?, until }
+        },
+    };
+
+    let mut enclosures = state.lock().await;
+    let state_of_enclosure = enclosure_mut(&mut enclosures, &id)?;
+    bad_request_if(until.is_some_and(|until| until <= Time::today(state_of_enclosure.timezone)),
+        "until must be a date in the future".to_string())?;
+    state_of_enclosure.automation = automation;
+    drop(enclosures);
+
+    log::info!("configured automation of enclosure {id} as {:?}", query.kind);
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok("Successfully configured automation")
+}
@@ -0,0 +1,32 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, enclosure_mut};
+
+#[utoipa::path(
+    delete, path = "/enclosures/{id}/configuration/brumation",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Successfully removed brumation program, computed timers are used again"),
+        (status = 404, description = "Enclosure not yet configured or no brumation program"),
+    ),
+)]
+pub async fn delete_brumation(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<&'static str> {
+    let mut enclosures = state.lock().await;
+    if enclosure_mut(&mut enclosures, &id)?.year_timer.set_brumation(None).is_none() {
+        return Err((StatusCode::NOT_FOUND, "No brumation program".to_string()));
+    }
+    drop(enclosures);
+
+    log::info!("removed brumation program for enclosure {id}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok("Successfully removed brumation program")
+}
@@ -0,0 +1,62 @@
+use axum::{extract, Json, http::StatusCode};
+use chrono::NaiveDate;
+
+use crate::timer::{day, brumation};
+use crate::state::StateWrapper;
+use crate::api::{WebResponse, enclosure};
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct GetBrumationResponse {
+    program: brumation::Program,
+
+    /// First local date after the program, using the computed timers again
+    #[schema(value_type = String, example = "2026-01-24")]
+    end: NaiveDate,
+
+    /// Resulting timers of every day of the program
+    days: Vec<BrumationDay>,
+}
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct BrumationDay {
+    #[schema(value_type = String, example = "2025-11-01")]
+    date: NaiveDate,
+
+    phase: brumation::Phase,
+
+    /// Effective timer, considering overrides
+    timer: day::Timer,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration/brumation",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Got brumation program and its resulting timers", body = GetBrumationResponse),
+        (status = 404, description = "Enclosure not yet configured or no brumation program"),
+    ),
+)]
+pub async fn get_brumation(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<Json<GetBrumationResponse>> {
+    let enclosures = state.lock().await;
+    let year_timer = &enclosure(&enclosures, &id)?.year_timer;
+    let Some(&program) = year_timer.brumation() else {
+        return Err((StatusCode::NOT_FOUND, "No brumation program".to_string()));
+    };
+
+    let end = program.end();
+    let days = program.start.iter_days()
+        .take_while(|date| *date < end)
+        .filter_map(|date| program.phase_on(date).map(|(phase, _)| BrumationDay {
+            date,
+            phase,
+            timer: year_timer.for_date(date).into_owned(),
+        }))
+        .collect();
+    Ok(Json(GetBrumationResponse { program, end, days }))
+}
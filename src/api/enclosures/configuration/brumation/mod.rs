@@ -1,3 +1,3 @@
-pub mod today;
 pub mod get;
 pub mod put;
+pub mod delete;
@@ -0,0 +1,70 @@
+use axum::extract;
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use crate::timer::brumation;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, bad_request_if, enclosure_mut};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutBrumationQuery {
+    /// First local date of shortening the photoperiod (`YYYY-MM-DD`)
+    #[param(value_type = String, example = "2025-11-01")]
+    start: NaiveDate,
+
+    /// Weeks to progressively shorten the photoperiod to `target_photoperiod_minutes`
+    #[param(example = 3)]
+    ramp_down_weeks: u8,
+
+    /// Weeks to hold the target photoperiod
+    #[param(example = 8)]
+    hold_weeks: u8,
+
+    /// Weeks to progressively lengthen the photoperiod back to the computed one
+    #[param(example = 3)]
+    ramp_up_weeks: u8,
+
+    /// Minutes to shorten the photoperiod to, centered on the computed one. Computed photoperiods already shorter are kept.
+    #[param(maximum = 1439, example = 480)]
+    target_photoperiod_minutes: u16,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/configuration/brumation",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), PutBrumationQuery),
+    responses(
+        (status = 200, description = "Successfully configured brumation program, replacing any previous one"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn put_brumation(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<PutBrumationQuery>,
+) -> WebResponse<String> {
+    bad_request_if(query.ramp_down_weeks == 0 && query.hold_weeks == 0 && query.ramp_up_weeks == 0,
+        "ramp_down_weeks, hold_weeks and ramp_up_weeks must not all be 0".to_string())?;
+    bad_request_if(query.target_photoperiod_minutes >= 24 * 60, "target_photoperiod_minutes must be less than a day".to_string())?;
+
+    let program = brumation::Program {
+        start: query.start,
+        ramp_down_weeks: query.ramp_down_weeks,
+        hold_weeks: query.hold_weeks,
+        ramp_up_weeks: query.ramp_up_weeks,
+        target_photoperiod_minutes: query.target_photoperiod_minutes,
+    };
+
+    let mut enclosures = state.lock().await;
+    enclosure_mut(&mut enclosures, &id)?.year_timer.set_brumation(Some(program));
+    drop(enclosures);
+
+    log::info!("configured brumation program from {} to {} for enclosure {id}", program.start, program.end());
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully configured brumation program from {} until {}", program.start, program.end()))
+}
@@ -0,0 +1,87 @@
+use axum::{extract, Json};
+use chrono::{Datelike, NaiveDate};
+
+use crate::time::{Time, Localized};
+use crate::state::StateWrapper;
+use crate::api::{WebResponse, bad_request_if, enclosure};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct GetDstQuery {
+    /// Year to report on, defaults to the current one
+    #[param(example = 2025)]
+    year: Option<i32>,
+}
+
+/// how a timer is affected by a DST change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DstKind {
+    /// Local time does not exist, the plug is switched later by the length of the gap
+    Skipped,
+    /// Local time occurs twice, the plug is only switched at the first occurrence
+    Ambiguous,
+}
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct DstTimer {
+    /// Name of the plug
+    #[schema(example = "main")]
+    plug: String,
+    /// Local date of the timer
+    #[schema(value_type = String, example = "2025-03-30")]
+    date: NaiveDate,
+    /// Whether the plug is turned on or off
+    power: bool,
+    /// Configured local time of the timer
+    time: Time,
+    kind: DstKind,
+    /// When the plug is actually switched
+    #[schema(value_type = String, example = "2025-03-30T01:30:00Z")]
+    switched_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration/dst",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), GetDstQuery),
+    responses(
+        (status = 200, description = "Got timers of all plugs which fall into local times skipped or repeated by DST changes of the configured timezone", body = Vec<DstTimer>),
+        (status = 400, description = "Invalid year"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn get_configuration_dst(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<GetDstQuery>,
+) -> WebResponse<Json<Vec<DstTimer>>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+
+    let year = query.year.unwrap_or_else(|| Time::today(state.timezone).year());
+    let first = NaiveDate::from_ymd_opt(year, 1, 1);
+    bad_request_if(first.is_none(), format!("Year {year} is out of range"))?;
+
+    let mut timers = Vec::new();
+    for date in first.unwrap().iter_days().take_while(|date| date.year() == year) {
+        for (name, device) in &state.plugs {
+            let day_timer = device.day_timer(&state.year_timer, date);
+            let transitions = day_timer.intervals().iter().flat_map(|interval| [
+                (date, *interval.on_time(), true),
+                (interval.off_date(date), *interval.off_time(), false),
+            ]);
+            for (date, time, power) in transitions {
+                let (kind, switched_at) = match time.on(date, state.timezone) {
+                    Localized::Unique(_) => continue,
+                    Localized::Skipped(moment) => (DstKind::Skipped, moment),
+                    Localized::Ambiguous(moment) => (DstKind::Ambiguous, moment),
+                };
+                timers.push(DstTimer { plug: name.clone(), date, power, time, kind, switched_at });
+            }
+        }
+    }
+
+    Ok(Json(timers))
+}
@@ -0,0 +1,158 @@
+use axum::{extract, Json};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use crate::time::Time;
+use crate::timer::{day, year::{Blending, Twilight}, adjustment::Adjustment};
+use crate::actuator::Actuator;
+use crate::api::{WebResponse, enclosure, enclosures::plug::get::GetPlugResponse};
+use crate::state::{StateWrapper, Mode, Automation};
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct GetConfigurationResponse {
+    /// How the computed timers are determined
+    mode: Mode,
+
+    /// Average sunrise/sunset times between local ones (`0.0`) and ones from the natural habitat (`1.0`).
+    /// This and the following coordinates are only present in astronomical mode.
+    #[schema(minimum = 0.0, maximum = 1.0, example = 0.5)]
+    natural_factor: Option<f32>,
+
+    /// Natural factors for the 15th of every month from January to December, linearly interpolated in between.
+    /// Only present if given instead of a single `natural_factor`, which is their average then.
+    #[schema(min_items = 12, max_items = 12)]
+    natural_factors: Option<[f32; 12]>,
+
+    /// How local days and days of the natural habitat are combined
+    blending: Option<Blending>,
+
+    /// Which times of a day define turning on and off
+    twilight: Option<Twilight>,
+
+    /// Latitude of geographic coordinates of terrarium, from -65° (south) to 65° (north) (limits exclusive)
+    #[schema(minimum = -65.0, maximum = 65.0)]
+    local_latitude: Option<f32>,
+
+    /// Longitude of geographic coordinates of terrarium, from -180° (west) to 180° (east)
+    #[schema(minimum = -180.0, maximum = 180.0)]
+    local_longitude: Option<f32>,
+
+    /// Latitude of geographic coordinates of the animals natural habitat, from -65° (south) to 65° (north) (limits exclusive)
+    #[schema(minimum = -65.0, maximum = 65.0)]
+    natural_latitude: Option<f32>,
+
+    /// Longitude of geographic coordinates of the animals natural habitat, from -180° (west) to 180° (east)
+    #[schema(minimum = -180.0, maximum = 180.0)]
+    natural_longitude: Option<f32>,
+
+    /// Offsets and limits applied to all computed timers below
+    adjustment: Option<Adjustment>,
+
+    /// Plugs to control
+    plugs: Vec<GetPlugResponse>,
+
+    /// IANA timezone to use for timer activations
+    #[schema(example = "Europe/Berlin")]
+    timezone: String,
+
+    /// Whether the expected power state of plugs is periodically enforced
+    reconcile: bool,
+
+    /// Whether plugs are switched automatically
+    automation: Automation,
+
+    /// Progress of moving gradually from previous computed timers to the current ones, if not yet finished
+    transition: Option<TransitionProgress>,
+
+    /// Timers to turn plug on/off every day, computed with given `natural_factor` (or `natural_factors`), including possible leap day.
+    /// Includes changes of specific dates like overrides in the next 366 days, starting today.
+    #[serde(with = "serde_big_array::BigArray")]
+    #[schema(min_items = 366, max_items = 366)]
+    computed_timers: [day::Timer; 366],
+
+    /// Theoretical timers to turn plug on/off every day if `natural_factor` was `0.0`, including possible leap day
+    #[serde(with = "serde_big_array::BigArray")]
+    #[schema(min_items = 366, max_items = 366)]
+    local_timers: [day::Timer; 366],
+
+    /// Theoretical timers to turn plug on/off every day if `natural_factor` was `1.0`, including possible leap day
+    #[serde(with = "serde_big_array::BigArray")]
+    #[schema(min_items = 366, max_items = 366)]
+    natural_timers: [day::Timer; 366],
+}
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct TransitionProgress {
+    /// First local date moving towards the new timers
+    #[schema(value_type = String, example = "2025-06-21")]
+    first_date: NaiveDate,
+
+    /// First local date using the new timers
+    #[schema(value_type = String, example = "2025-07-05")]
+    end_date: NaiveDate,
+
+    /// Days of the transition including `end_date`
+    #[schema(example = 14)]
+    days: usize,
+
+    /// Days of the transition up to and including today
+    #[schema(example = 3)]
+    days_passed: usize,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Got configuration", body = GetConfigurationResponse),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+#[allow(clippy::significant_drop_tightening)]
+pub async fn get_configuration(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(actuator): extract::State<Actuator>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<Json<GetConfigurationResponse>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+    let astronomical = state.mode == Mode::Astronomical;
+
+    Ok(Json(GetConfigurationResponse {
+        mode: state.mode.clone(),
+        natural_factor: state.natural_factor,
+        natural_factors: state.natural_factors.filter(|_| astronomical),
+        blending: astronomical.then_some(state.blending),
+        twilight: astronomical.then_some(state.twilight),
+        local_latitude: state.local_latitude,
+        local_longitude: state.local_longitude,
+        natural_latitude: state.natural_latitude,
+        natural_longitude: state.natural_longitude,
+        adjustment: astronomical.then_some(state.adjustment),
+        plugs: state.plugs.iter()
+            .map(|(name, device)| GetPlugResponse::new(&id, name, device, &state.year_timer, Time::today(state.timezone), &actuator))
+            .collect(),
+        timezone: state.timezone.to_string(),
+        reconcile: state.reconcile,
+        automation: state.automation.clone(),
+        transition: transition_progress(state.year_timer.transition(), Time::today(state.timezone)),
+        computed_timers: state.year_timer.upcoming_day_timers(Time::today(state.timezone)),
+        local_timers: state.local_year_timer.day_timers().clone(),
+        natural_timers: state.natural_year_timer.day_timers().clone(),
+    }))
+}
+
+/// progress of a transition given by its interpolated day timers, if it ends after `today`
+fn transition_progress(transition: &BTreeMap<NaiveDate, day::Timer>, today: NaiveDate) -> Option<TransitionProgress> {
+    let first_date = *transition.first_key_value()?.0;
+    let end_date = transition.last_key_value()?.0.succ_opt()?;
+    (today < end_date).then(|| TransitionProgress {
+        first_date,
+        end_date,
+        days: transition.len() + 1,
+        days_passed: transition.range(..=today).count(),
+    })
+}
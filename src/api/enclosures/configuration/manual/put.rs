@@ -0,0 +1,105 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+use chrono_tz::Tz;
+
+use crate::time::Time;
+use crate::timer::year;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper, Mode};
+use crate::api::{WebResponse, bad_request_if, parse_timer, enclosures::plug::PlugQuery};
+use super::super::{plugs_with, default_plug_name};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutManualConfigurationQuery {
+    /// Name of the plug to configure, following the computed timers. Other plugs are kept.
+    #[serde(default = "default_plug_name")]
+    #[param(default = "main")]
+    plug_name: String,
+
+    /// Intervals to turn the plug on/off every day (`HH:MM-HH:MM`, comma-separated),
+    /// e.g. `08:00-20:00` for 12 hours of light. Required unless `monthly` is given.
+    #[param(example = "08:00-20:00")]
+    daily: Option<String>,
+
+    /// Timers for the 15th of every month from January to December (semicolon-separated),
+    /// each formatted like `daily` and with the same number of intervals.
+    /// Days in between are interpolated linearly.
+    #[param(example = "09:00-17:00;08:30-17:30;08:00-18:00;07:30-18:30;07:00-19:00;06:30-19:30;06:30-19:30;07:00-19:00;07:30-18:30;08:00-18:00;08:30-17:30;09:00-17:00")]
+    monthly: Option<String>,
+
+    /// IANA timezone to use for timer activations, defaults to the local timezone.
+    /// Rejected if it is not a known IANA timezone.
+    #[param(example = "Europe/Berlin")]
+    timezone: Option<String>,
+
+    /// Whether to periodically enforce the expected power state of plugs (e.g. after a restart,
+    /// failed commands or switching by hand), instead of only switching them when a timer matches
+    #[serde(default)]
+    #[param(default = false)]
+    reconcile: bool,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/configuration/manual",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), PutManualConfigurationQuery, PlugQuery),
+    responses(
+        (status = 200, description = "Successfully configured timers without sunrise data, adding enclosure if not yet configured"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+    ),
+)]
+pub async fn put_manual_configuration(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<PutManualConfigurationQuery>,
+    extract::Query(plug_query): extract::Query<PlugQuery>,
+) -> WebResponse<&'static str> {
+    bad_request_if(query.plug_name.is_empty(), "plug_name must not be empty".to_string())?;
+
+    let (mode, mut year_timer) = match (query.daily, query.monthly) {
+        (Some(daily), None) => {
+            let timer = parse_timer(&daily)?;
+            let year_timer = year::Timer::daily(&timer);
+            (Mode::Daily { timer }, year_timer)
+        },
+        (None, Some(monthly)) => {
+            let timers = monthly.split(';').map(parse_timer).collect::<WebResponse<Vec<_>>>()?;
+            let Ok(timers) = <[_; 12]>::try_from(timers) else {
+                return Err((StatusCode::BAD_REQUEST, "monthly must contain exactly 12 timers".to_string()));
+            };
+            let year_timer = year::Timer::monthly(&timers).map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+            (Mode::Monthly { timers: Box::new(timers) }, year_timer)
+        },
+        _ => return Err((StatusCode::BAD_REQUEST, "exactly one of daily and monthly is required".to_string())),
+    };
+
+    let timezone = match query.timezone.as_deref() {
+        Some(timezone) => timezone.parse::<Tz>()
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("timezone \"{timezone}\" is not a valid IANA timezone")))?,
+        None => Time::local_zone(),
+    };
+
+    let plug = plug_query.plug().await?;
+    // same as computed ones, but without overrides
+    let local_year_timer = year_timer.clone();
+    log::info!("configured timers manually");
+
+    let mut enclosures = state.lock().await;
+    let plugs = plugs_with(&enclosures, &id, query.plug_name, plug);
+    if let Some(previous) = enclosures.get(&id) {
+        year_timer.keep_layers_of(&previous.year_timer);
+    }
+    let automation = enclosures.get(&id).map(|previous| previous.automation.clone()).unwrap_or_default();
+    enclosures.insert(id, State {
+        reconcile: query.reconcile,
+        automation,
+        ..State::new(mode, plugs, timezone, year_timer, local_year_timer.clone(), local_year_timer)
+    });
+    drop(enclosures);
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+
+    Ok("Successfully configured timers")
+}
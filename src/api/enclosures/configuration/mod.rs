@@ -0,0 +1,30 @@
+pub mod automation;
+pub mod brumation;
+pub mod dst;
+pub mod manual;
+pub mod overrides;
+pub mod today;
+pub mod get;
+pub mod put;
+
+use std::collections::BTreeMap;
+
+use crate::plug::Plug;
+use crate::state::Enclosures;
+use crate::device::{Device, Schedule};
+
+/// name of the plug to configure if none is given
+fn default_plug_name() -> String { String::from("main") }
+
+/// plugs of enclosure with given id after configuring `plug` to follow the computed timers,
+/// keeping other plugs and other settings of a plug with the same name
+fn plugs_with(enclosures: &Enclosures, id: &str, plug_name: String, plug: Plug) -> BTreeMap<String, Device> {
+    let mut plugs = enclosures.get(id).map(|state| state.plugs.clone()).unwrap_or_default();
+    plugs.entry(plug_name)
+        .and_modify(|device| {
+            device.plug = plug.clone();
+            device.schedule = Schedule::Computed;
+        })
+        .or_insert_with(|| Device::new(plug, Schedule::Computed));
+    plugs
+}
@@ -0,0 +1,33 @@
+use axum::{extract, http::StatusCode};
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, enclosure_mut};
+
+#[utoipa::path(
+    delete, path = "/enclosures/{id}/configuration/overrides/{date}",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("date" = String, Path, description = "Local date (`YYYY-MM-DD`)")),
+    responses(
+        (status = 200, description = "Successfully removed override, computed timer is used again"),
+        (status = 404, description = "Enclosure not yet configured or no override on given date"),
+    ),
+)]
+pub async fn delete_override(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, date)): extract::Path<(String, NaiveDate)>,
+) -> WebResponse<String> {
+    let mut enclosures = state.lock().await;
+    if enclosure_mut(&mut enclosures, &id)?.year_timer.remove_override(date).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("No override on {date}")));
+    }
+    drop(enclosures);
+
+    log::info!("removed override on {date} for enclosure {id}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully removed override on {date}"))
+}
@@ -0,0 +1,58 @@
+use axum::{extract, Json, http::StatusCode};
+use chrono::NaiveDate;
+
+use crate::timer::day;
+use crate::api::{WebResponse, enclosure};
+use crate::state::StateWrapper;
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct GetOverrideResponse {
+    /// Local date the computed timer is replaced on
+    #[schema(value_type = String, example = "2025-12-24")]
+    date: NaiveDate,
+
+    /// Timer replacing the computed one
+    timer: day::Timer,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration/overrides",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Got all overrides, ordered by date", body = Vec<GetOverrideResponse>),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn get_overrides(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<Json<Vec<GetOverrideResponse>>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+    Ok(Json(state.year_timer.overrides().iter()
+        .map(|(date, timer)| GetOverrideResponse { date: *date, timer: timer.clone() })
+        .collect()))
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration/overrides/{date}",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("date" = String, Path, description = "Local date (`YYYY-MM-DD`)")),
+    responses(
+        (status = 200, description = "Got override", body = day::Timer),
+        (status = 404, description = "Enclosure not yet configured or no override on given date"),
+    ),
+)]
+pub async fn get_override(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path((id, date)): extract::Path<(String, NaiveDate)>,
+) -> WebResponse<Json<day::Timer>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+    let Some(timer) = state.year_timer.overrides().get(&date) else {
+        return Err((StatusCode::NOT_FOUND, format!("No override on {date}")));
+    };
+    Ok(Json(timer.clone()))
+}
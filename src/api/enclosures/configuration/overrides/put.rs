@@ -0,0 +1,49 @@
+use axum::extract;
+use chrono::NaiveDate;
+use std::sync::Arc;
+
+use crate::timer::day;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, enclosure_mut, parse_timer};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutOverrideQuery {
+    /// Intervals to turn plugs on/off on this date instead of the computed timer (`HH:MM-HH:MM`, comma-separated).
+    /// If missing, they stay off the whole day. Plugs with schedule `fixed` are not affected,
+    /// other schedules are derived from the override instead.
+    #[param(example = "07:00-22:00")]
+    intervals: Option<String>,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/configuration/overrides/{date}",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("date" = String, Path, description = "Local date (`YYYY-MM-DD`)"), PutOverrideQuery),
+    responses(
+        (status = 200, description = "Successfully replaced computed timer on given date"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn put_override(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, date)): extract::Path<(String, NaiveDate)>,
+    extract::Query(query): extract::Query<PutOverrideQuery>,
+) -> WebResponse<String> {
+    let timer = match query.intervals {
+        Some(intervals) => parse_timer(&intervals)?,
+        None => day::Timer::off(),
+    };
+
+    let mut enclosures = state.lock().await;
+    enclosure_mut(&mut enclosures, &id)?.year_timer.set_override(date, timer);
+    drop(enclosures);
+
+    log::info!("configured override on {date} for enclosure {id}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully configured override on {date}"))
+}
@@ -0,0 +1,254 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+
+use super::{plugs_with, default_plug_name};
+use crate::time::Time;
+use crate::timer::{year::{self, Blending, Twilight}, adjustment::Adjustment};
+use crate::sunrise_api::request;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper, Mode};
+use crate::api::{WebResponse, bad_request_if, parse_time, enclosures::plug::PlugQuery};
+use crate::constants::{MIN_SUNRISE_API_REQUEST_INTERVAL, ABS_POLAR_CIRCLE_LAT};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutConfigurationQuery {
+    /// Name of the plug to configure, following the computed timers. Other plugs are kept.
+    #[serde(default = "default_plug_name")]
+    #[param(default = "main")]
+    plug_name: String,
+
+    /// Average sunrise/sunset times between local ones (`0.0`) and ones from the natural habitat (`1.0`).
+    /// Either this or `natural_factors` is required.
+    #[param(minimum = 0.0, maximum = 1.0, example = 0.5)]
+    natural_factor: Option<f32>,
+
+    /// Natural factors for the 15th of every month from January to December (comma-separated),
+    /// linearly interpolated in between, to vary the average across the year instead of `natural_factor`
+    #[param(example = "0.2,0.2,0.4,0.6,0.8,0.8,0.8,0.8,0.6,0.4,0.2,0.2")]
+    natural_factors: Option<String>,
+
+    /// How to combine local days and days of the natural habitat
+    #[serde(default)]
+    #[param(inline, default = "local_center")]
+    blending: Blending,
+
+    /// Which times of a day define turning on and off
+    #[serde(default)]
+    #[param(inline, default = "sunrise")]
+    twilight: Twilight,
+
+    /// Latitude of geographic coordinates of terrarium, from -65° (south) to 65° (north) (limits exclusive)
+    #[param(minimum = -65.0, maximum = 65.0)]
+    local_latitude: f32,
+
+    /// Longitude of geographic coordinates of terrarium, from -180° (west) to 180° (east)
+    #[param(minimum = -180.0, maximum = 180.0)]
+    local_longitude: f32,
+
+    /// Latitude of geographic coordinates of the animals natural habitat, from -65° (south) to 65° (north) (limits exclusive)
+    #[param(minimum = -65.0, maximum = 65.0)]
+    natural_latitude: f32,
+
+    /// Longitude of geographic coordinates of the animals natural habitat, from -180° (west) to 180° (east)
+    #[param(minimum = -180.0, maximum = 180.0)]
+    natural_longitude: f32,
+
+    /// Minutes to turn on later than computed (e.g. after sunrise), negative meaning earlier
+    #[serde(default)]
+    #[param(minimum = -1439, maximum = 1439, default = 0, example = 30)]
+    on_offset_minutes: i16,
+
+    /// Minutes to turn off later than computed (e.g. after sunset), negative meaning earlier
+    #[serde(default)]
+    #[param(minimum = -1439, maximum = 1439, default = 0, example = -15)]
+    off_offset_minutes: i16,
+
+    /// Minimum minutes to be on every day, applied after the offsets by extending the photoperiod equally on both ends
+    #[param(maximum = 1440, example = 600)]
+    min_photoperiod_minutes: Option<u16>,
+
+    /// Maximum minutes to be on every day, applied after the offsets by shortening the photoperiod equally on both ends
+    #[param(maximum = 1440, example = 840)]
+    max_photoperiod_minutes: Option<u16>,
+
+    /// Time to never turn on before (`HH:MM`), applied last
+    #[param(example = "07:00")]
+    earliest_on_time: Option<String>,
+
+    /// Time to never turn off after (`HH:MM`), applied last
+    #[param(example = "21:00")]
+    latest_off_time: Option<String>,
+
+    /// Days to move gradually from the previous computed timers to the new ones, instead of switching at once.
+    /// Starts today, the last day uses the new timers.
+    #[param(minimum = 1, maximum = 366, example = 14)]
+    transition_days: Option<u16>,
+
+    /// Maximum minutes to move on and off times per day when moving gradually from the previous computed timers,
+    /// instead of `transition_days`
+    #[param(minimum = 1, example = 10)]
+    transition_max_minutes_per_day: Option<u16>,
+
+    /// Whether to periodically enforce the expected power state of plugs (e.g. after a restart,
+    /// failed commands or switching by hand), instead of only switching them when a timer matches
+    #[serde(default)]
+    #[param(default = false)]
+    reconcile: bool,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/configuration",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), PutConfigurationQuery, PlugQuery),
+    responses(
+        (status = 200, description = "Successfully configured timers, adding enclosure if not yet configured"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 429, description = "Reached sunrise API request rate limit"),
+        (status = 502, description = "Unexpected response from sunrise API"),
+    ),
+)]
+pub async fn put_configuration(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<PutConfigurationQuery>,
+    extract::Query(plug_query): extract::Query<PlugQuery>,
+) -> WebResponse<&'static str> {
+    let local_latitude = query.local_latitude;
+    let local_longitude = query.local_longitude;
+    let natural_latitude = query.natural_latitude;
+    let natural_longitude = query.natural_longitude;
+
+    bad_request_if(query.plug_name.is_empty(), "plug_name must not be empty".to_string())?;
+    let (natural_factor, natural_factors) = natural_factors(&query)?;
+    bad_request_if(!(-180. ..= 180.).contains(&local_longitude), "local_longitude must be between -180.0 and 180.0".to_string())?;
+    bad_request_if(!(-180. ..= 180.).contains(&natural_longitude), "natural_longitude must be between -180.0 and 180.0".to_string())?;
+    bad_request_if(  local_latitude <= -ABS_POLAR_CIRCLE_LAT ||   local_latitude >= ABS_POLAR_CIRCLE_LAT,
+        format!("local_latitude must be between -{ABS_POLAR_CIRCLE_LAT:.1} and {ABS_POLAR_CIRCLE_LAT:.1} (limits exclusive)"))?;
+    bad_request_if(natural_latitude <= -ABS_POLAR_CIRCLE_LAT || natural_latitude >= ABS_POLAR_CIRCLE_LAT,
+        format!("natural_latitude must be between -{ABS_POLAR_CIRCLE_LAT:.1} and {ABS_POLAR_CIRCLE_LAT:.1} (limits exclusive)"))?;
+
+    let adjustment = adjustment(&query)?;
+    bad_request_if(query.transition_days.is_some() && query.transition_max_minutes_per_day.is_some(),
+        "Only one of transition_days and transition_max_minutes_per_day may be given".to_string())?;
+    bad_request_if(query.transition_days.is_some_and(|days| !(1 ..= 366).contains(&days)),
+        "transition_days must be between 1 and 366".to_string())?;
+    bad_request_if(query.transition_max_minutes_per_day == Some(0), "transition_max_minutes_per_day must be at least 1".to_string())?;
+
+    let plug = plug_query.plug().await?;
+
+    let local_api_days = request(local_latitude, local_longitude).await?;
+
+    let local_is_natural =
+        (local_latitude  - natural_latitude ).abs() < f32::EPSILON &&
+        (local_longitude - natural_longitude).abs() < f32::EPSILON;
+
+    let natural_api_days = if local_is_natural {
+        log::debug!("using API response for local location as response for natural location");
+        local_api_days.clone()
+    } else {
+        tokio::time::sleep(MIN_SUNRISE_API_REQUEST_INTERVAL).await; // avoid API rate limiting
+        request(natural_latitude, natural_longitude).await?
+    };
+
+    let (timezone, year_timer, local_year_timer, natural_year_timer) =
+        year::Timer::from_api_days_average(
+            &natural_factors.map_or([natural_factor; 366], |natural_factors| year::Timer::monthly_natural_factors(&natural_factors)),
+            query.blending,
+            query.twilight,
+            &local_api_days,
+            &natural_api_days,
+        )?;
+
+    let adjust = |year_timer: year::Timer| year_timer.adjusted(&adjustment).map_err(|error| (StatusCode::BAD_REQUEST, error));
+    let mut year_timer = adjust(year_timer)?;
+    let local_year_timer = adjust(local_year_timer)?;
+    let natural_year_timer = adjust(natural_year_timer)?;
+    log::info!("configured timers");
+
+    let mut enclosures = state.lock().await;
+    let plugs = plugs_with(&enclosures, &id, query.plug_name, plug);
+    if let Some(previous) = enclosures.get(&id) {
+        year_timer.keep_layers_of(&previous.year_timer);
+
+        let today = Time::today(timezone);
+        let days = match (query.transition_days, query.transition_max_minutes_per_day) {
+            (Some(days), _) => days,
+            (None, Some(max_minutes)) => year_timer.transition_days(&previous.year_timer, today, max_minutes),
+            (None, None) => 1,
+        };
+        year_timer.transition_from(&previous.year_timer, today, days).map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+        if days > 1 {
+            log::info!("moving gradually to new timers over {days} days");
+        }
+    }
+    let automation = enclosures.get(&id).map(|previous| previous.automation.clone()).unwrap_or_default();
+    enclosures.insert(id, State {
+        natural_factor: Some(natural_factor),
+        natural_factors,
+        blending: query.blending,
+        twilight: query.twilight,
+        local_latitude: Some(local_latitude),
+        local_longitude: Some(local_longitude),
+        natural_latitude: Some(natural_latitude),
+        natural_longitude: Some(natural_longitude),
+        adjustment,
+        reconcile: query.reconcile,
+        automation,
+        ..State::new(Mode::Astronomical, plugs, timezone, year_timer, local_year_timer, natural_year_timer)
+    });
+    drop(enclosures);
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+
+    Ok("Successfully configured timers")
+}
+
+/// validated natural factor from query parameters, which is the average of the monthly ones if given instead
+fn natural_factors(query: &PutConfigurationQuery) -> WebResponse<(f32, Option<[f32; 12]>)> {
+    match (query.natural_factor, query.natural_factors.as_deref()) {
+        (Some(natural_factor), None) => {
+            bad_request_if(!(0. ..= 1.).contains(&natural_factor), "natural_factor must be between 0.0 and 1.0".to_string())?;
+            Ok((natural_factor, None))
+        },
+        (None, Some(natural_factors)) => {
+            let error = || (StatusCode::BAD_REQUEST, "natural_factors must be 12 comma-separated numbers between 0.0 and 1.0".to_string());
+            let natural_factors = natural_factors.split(',')
+                .map(|natural_factor| natural_factor.trim().parse::<f32>().ok().filter(|natural_factor| (0. ..= 1.).contains(natural_factor)))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(error)?;
+            let natural_factors = <[f32; 12]>::try_from(natural_factors).map_err(|_| error())?;
+            Ok((natural_factors.iter().sum::<f32>() / 12., Some(natural_factors)))
+        },
+        _ => Err((StatusCode::BAD_REQUEST, "exactly one of natural_factor and natural_factors is required".to_string())),
+    }
+}
+
+/// validated adjustment of the computed timers from query parameters
+fn adjustment(query: &PutConfigurationQuery) -> WebResponse<Adjustment> {
+    let limit = 24 * 60;
+    bad_request_if(query.on_offset_minutes.abs() >= limit || query.off_offset_minutes.abs() >= limit,
+        "on_offset_minutes and off_offset_minutes must be less than a day".to_string())?;
+    bad_request_if(query.min_photoperiod_minutes.is_some_and(|minutes| minutes > limit.unsigned_abs())
+        || query.max_photoperiod_minutes.is_some_and(|minutes| minutes > limit.unsigned_abs()),
+        "min_photoperiod_minutes and max_photoperiod_minutes must be at most a day".to_string())?;
+    if let (Some(min), Some(max)) = (query.min_photoperiod_minutes, query.max_photoperiod_minutes) {
+        bad_request_if(min > max, "min_photoperiod_minutes must not exceed max_photoperiod_minutes".to_string())?;
+    }
+
+    let earliest_on_time = query.earliest_on_time.as_deref().map(|time| parse_time(time, "earliest_on_time")).transpose()?;
+    let latest_off_time = query.latest_off_time.as_deref().map(|time| parse_time(time, "latest_off_time")).transpose()?;
+    if let (Some(earliest), Some(latest)) = (earliest_on_time, latest_off_time) {
+        bad_request_if(earliest >= latest, "earliest_on_time must be before latest_off_time".to_string())?;
+    }
+
+    Ok(Adjustment {
+        on_offset_minutes: query.on_offset_minutes,
+        off_offset_minutes: query.off_offset_minutes,
+        min_photoperiod_minutes: query.min_photoperiod_minutes,
+        max_photoperiod_minutes: query.max_photoperiod_minutes,
+        earliest_on_time,
+        latest_off_time,
+    })
+}
@@ -0,0 +1,43 @@
+use axum::{extract, Json, http::StatusCode};
+
+use crate::time::Time;
+use crate::timer::day;
+use crate::api::{WebResponse, enclosure};
+use crate::state::StateWrapper;
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct GetConfigurationTodayQuery {
+    /// Name of the plug to get the timer in effect for, which is the one of yesterday
+    /// while it lasts past midnight. Defaults to the computed timer of today.
+    #[param(example = "main")]
+    plug: Option<String>,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/configuration/today",
+    tag = "Configuration",
+    params(("id" = String, Path, description = "Id of the enclosure"), GetConfigurationTodayQuery),
+    responses(
+        (status = 200, description = "Got todays configuration", body = day::Timer),
+        (status = 404, description = "Enclosure not yet configured or no plug with given name"),
+    ),
+)]
+pub async fn get_configuration_today(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path(id): extract::Path<String>,
+    extract::Query(query): extract::Query<GetConfigurationTodayQuery>,
+) -> WebResponse<Json<day::Timer>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+
+    let Some(name) = query.plug else {
+        return Ok(Json(state.year_timer.for_today(state.timezone).into_owned()));
+    };
+    let Some(device) = state.plugs.get(&name) else {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    };
+
+    let (_, timer) = device.active_day_timer(&state.year_timer, Time::today(state.timezone), Time::now(state.timezone));
+    Ok(Json(timer))
+}
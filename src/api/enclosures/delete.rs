@@ -0,0 +1,30 @@
+use axum::extract;
+use std::sync::Arc;
+
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, not_configured};
+
+#[utoipa::path(
+    delete, path = "/enclosures/{id}",
+    tag = "Enclosures",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Successfully removed enclosure"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn delete_enclosure(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<String> {
+    if state.lock().await.remove(&id).is_none() {
+        return Err(not_configured(&id));
+    }
+
+    log::info!("removed enclosure {id}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully removed enclosure \"{id}\""))
+}
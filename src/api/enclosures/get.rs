@@ -0,0 +1,16 @@
+use axum::{extract, Json};
+
+use crate::state::StateWrapper;
+
+#[utoipa::path(
+    get, path = "/enclosures",
+    tag = "Enclosures",
+    responses(
+        (status = 200, description = "Got ids of all configured enclosures", body = Vec<String>, example = json!(["main-terrarium"])),
+    ),
+)]
+pub async fn get_enclosures(
+    extract::State(state): extract::State<StateWrapper>
+) -> Json<Vec<String>> {
+    Json(state.lock().await.keys().cloned().collect())
+}
@@ -0,0 +1,4 @@
+pub mod configuration;
+pub mod plug;
+pub mod get;
+pub mod delete;
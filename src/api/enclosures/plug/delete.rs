@@ -0,0 +1,32 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+
+use crate::api::{WebResponse, enclosure_mut};
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+
+#[utoipa::path(
+    delete, path = "/enclosures/{id}/plug/{name}",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug")),
+    responses(
+        (status = 200, description = "Successfully removed plug"),
+        (status = 404, description = "Enclosure not yet configured or no plug with given name"),
+    ),
+)]
+pub async fn delete_plug(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+) -> WebResponse<String> {
+    let mut enclosures = state.lock().await;
+    if enclosure_mut(&mut enclosures, &id)?.plugs.remove(&name).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    }
+    drop(enclosures);
+
+    log::info!("removed plug {name}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully removed plug \"{name}\""))
+}
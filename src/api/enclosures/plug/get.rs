@@ -0,0 +1,83 @@
+use axum::{extract, Json};
+use chrono::NaiveDate;
+
+use crate::retry;
+use crate::time::Time;
+use crate::timer::{day, year};
+use crate::actuator::{Actuator, PlugId};
+use crate::device::{Device, Schedule, PowerOverride};
+use crate::api::{WebResponse, enclosure};
+use crate::plug::PowerSwitch;
+use crate::state::StateWrapper;
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct GetPlugResponse {
+    /// Unique name of the plug
+    #[schema(example = "main")]
+    name: String,
+
+    /// Human-readable description of the plug
+    #[schema(example = "Shelly Gen1 plug at http://192.168.178.123")]
+    description: String,
+
+    /// URL to the plug (or its MQTT broker)
+    #[schema(example = "http://192.168.178.123")]
+    url: String,
+
+    /// Timers the plug follows
+    schedule: Schedule,
+
+    /// How to retry scheduled commands that failed
+    retry_policy: retry::Policy,
+
+    /// Status of the scheduled command currently being retried, if any
+    retry_status: Option<retry::Status>,
+
+    /// Power state set by hand that takes precedence over the schedule, if any
+    power_override: Option<PowerOverride>,
+
+    /// Resulting timers to turn the plug on/off every day, including possible leap day.
+    /// Includes changes of specific dates like overrides in the next 366 days, starting today.
+    #[serde(with = "serde_big_array::BigArray")]
+    #[schema(min_items = 366, max_items = 366)]
+    timers: [day::Timer; 366],
+}
+
+impl GetPlugResponse {
+    /// with the computed `year_timer` of the configuration to derive timers from,
+    /// the local date of `today` and the `actuator` to get the retry status from
+    pub fn new(id: &str, name: &str, device: &Device, year_timer: &year::Timer, today: NaiveDate, actuator: &Actuator) -> Self {
+        Self {
+            name: name.to_string(),
+            description: device.plug.describe(),
+            url: device.plug.get_url(),
+            schedule: device.schedule.clone(),
+            retry_policy: device.retry_policy,
+            retry_status: actuator.retry_status(&PlugId { enclosure: id.to_string(), name: name.to_string() }),
+            power_override: device.power_override,
+            timers: device.day_timers(year_timer, today),
+        }
+    }
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/plug",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure")),
+    responses(
+        (status = 200, description = "Got all configured plugs", body = Vec<GetPlugResponse>),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn get_plugs(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(actuator): extract::State<Actuator>,
+    extract::Path(id): extract::Path<String>,
+) -> WebResponse<Json<Vec<GetPlugResponse>>> {
+    let enclosures = state.lock().await;
+    let state = enclosure(&enclosures, &id)?;
+    Ok(Json(state.plugs.iter()
+        .map(|(name, device)| GetPlugResponse::new(&id, name, device, &state.year_timer, Time::today(state.timezone), &actuator))
+        .collect()))
+}
@@ -0,0 +1,181 @@
+pub mod power;
+pub mod power_override;
+pub mod get;
+pub mod put;
+pub mod delete;
+
+use axum::http::StatusCode;
+
+use crate::api::{WebResponse, bad_request_if};
+use crate::plug::{self, Plug, Error, PowerSwitch, MqttConfig, WebhookConfig, WebhookMethod};
+
+// from query parameters, for every endpoint configuring a plug
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PlugQuery {
+    /// Kind of smart plug to control
+    #[serde(default)]
+    #[param(inline)]
+    plug_kind: plug::Kind,
+
+    /// URL to smart plug without a trailing slash, required for kinds `shelly` and `tasmota`.
+    /// Shelly plugs need to be compatible with the
+    /// [Gen1 API](https://shelly-api-docs.shelly.cloud/gen1/#shelly-plug-plugs-relay-0) or the
+    /// [Gen2+ API](https://shelly-api-docs.shelly.cloud/gen2/ComponentsAndServices/Switch) (detected automatically),
+    /// Tasmota plugs with the [web request API](https://tasmota.github.io/docs/Commands/#with-web-requests).
+    #[param(example = "http://192.168.178.123")]
+    plug_url: Option<String>,
+
+    /// Hostname or IP address of MQTT broker, required for kind `mqtt`
+    #[param(example = "192.168.178.2")]
+    mqtt_host: Option<String>,
+
+    /// Port of MQTT broker
+    #[serde(default = "default_mqtt_port")]
+    #[param(default = 1883)]
+    mqtt_port: u16,
+
+    /// Username to authenticate at MQTT broker with
+    mqtt_username: Option<String>,
+
+    /// Password to authenticate at MQTT broker with
+    mqtt_password: Option<String>,
+
+    /// Topic to publish `mqtt_payload_on`/`mqtt_payload_off` to, required for kind `mqtt`
+    #[param(example = "zigbee2mqtt/terrarium-plug/set")]
+    mqtt_command_topic: Option<String>,
+
+    /// Topic the plug publishes its state to, required for kind `mqtt`
+    #[param(example = "zigbee2mqtt/terrarium-plug")]
+    mqtt_state_topic: Option<String>,
+
+    /// Payload to publish to turn the plug on
+    #[serde(default = "default_on")]
+    #[param(default = "ON", example = "{\"state\":\"ON\"}")]
+    mqtt_payload_on: String,
+
+    /// Payload to publish to turn the plug off
+    #[serde(default = "default_off")]
+    #[param(default = "OFF", example = "{\"state\":\"OFF\"}")]
+    mqtt_payload_off: String,
+
+    /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the state inside a JSON payload on `mqtt_state_topic`.
+    /// If not set, the whole payload is used as the state.
+    #[param(example = "/state")]
+    mqtt_state_pointer: Option<String>,
+
+    /// State meaning the plug is on (case insensitive)
+    #[serde(default = "default_on")]
+    #[param(default = "ON")]
+    mqtt_state_on: String,
+
+    /// State meaning the plug is off (case insensitive)
+    #[serde(default = "default_off")]
+    #[param(default = "OFF")]
+    mqtt_state_off: String,
+
+    /// URL to request to turn the plug on, required for kind `webhook`.
+    /// Webhook URLs and body may contain `{power}` (replaced with `true`/`false`) and `{turn}` (replaced with `on`/`off`).
+    #[param(example = "http://192.168.178.124/switch/relay/turn_on")]
+    webhook_on_url: Option<String>,
+
+    /// URL to request to turn the plug off, required for kind `webhook`
+    #[param(example = "http://192.168.178.124/switch/relay/turn_off")]
+    webhook_off_url: Option<String>,
+
+    /// HTTP method to request `webhook_on_url`/`webhook_off_url` with
+    #[serde(default)]
+    #[param(inline)]
+    webhook_method: WebhookMethod,
+
+    /// Body to send when requesting `webhook_on_url`/`webhook_off_url`
+    #[param(example = "{\"on\":{power}}")]
+    webhook_body: Option<String>,
+
+    /// URL to get the plugs power state from with a GET request responding with JSON, required for kind `webhook`
+    #[param(example = "http://192.168.178.124/switch/relay")]
+    webhook_state_url: Option<String>,
+
+    /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the power state inside the response from `webhook_state_url`,
+    /// like `true`, `"ON"` or `1`
+    #[serde(default = "default_webhook_state_pointer")]
+    #[param(default = "/state", example = "/value")]
+    webhook_state_pointer: String,
+}
+
+const fn default_mqtt_port() -> u16 { 1883 }
+fn default_on() -> String { String::from("ON") }
+fn default_off() -> String { String::from("OFF") }
+fn default_webhook_state_pointer() -> String { String::from("/state") }
+
+impl PlugQuery {
+    /// construct plug from query and test if it is reachable
+    pub async fn plug(&self) -> WebResponse<Plug> {
+        let plug = match self.plug_kind {
+            plug::Kind::Shelly => {
+                let plug = Plug::shelly(self.plug_url()?).await;
+                bad_request_if(plug.is_err(), "Could not detect Shelly plug using plug_url, make sure a compatible device is reachable".to_string())?;
+                plug.unwrap()
+            },
+            plug::Kind::Tasmota => Plug::tasmota(self.plug_url()?),
+            plug::Kind::Mqtt => {
+                let (Some(host), Some(command_topic), Some(state_topic)) = (&self.mqtt_host, &self.mqtt_command_topic, &self.mqtt_state_topic) else {
+                    return Err((StatusCode::BAD_REQUEST, String::from("mqtt_host, mqtt_command_topic and mqtt_state_topic are required for plug_kind mqtt")));
+                };
+                bad_request_if(self.mqtt_state_pointer.as_ref().is_some_and(|pointer| !pointer.starts_with('/')),
+                    "mqtt_state_pointer must start with a slash".to_string())?;
+                Plug::mqtt(MqttConfig {
+                    broker_host: host.clone(),
+                    broker_port: self.mqtt_port,
+                    username: self.mqtt_username.clone(),
+                    password: self.mqtt_password.clone(),
+                    command_topic: command_topic.clone(),
+                    state_topic: state_topic.clone(),
+                    payload_on: self.mqtt_payload_on.clone(),
+                    payload_off: self.mqtt_payload_off.clone(),
+                    state_pointer: self.mqtt_state_pointer.clone(),
+                    state_on: self.mqtt_state_on.clone(),
+                    state_off: self.mqtt_state_off.clone(),
+                })
+            },
+            plug::Kind::Webhook => {
+                let (Some(on_url), Some(off_url), Some(state_url)) = (&self.webhook_on_url, &self.webhook_off_url, &self.webhook_state_url) else {
+                    return Err((StatusCode::BAD_REQUEST, String::from("webhook_on_url, webhook_off_url and webhook_state_url are required for plug_kind webhook")));
+                };
+                bad_request_if(!self.webhook_state_pointer.starts_with('/'),
+                    "webhook_state_pointer must start with a slash".to_string())?;
+                Plug::webhook(WebhookConfig {
+                    on_url: on_url.clone(),
+                    off_url: off_url.clone(),
+                    method: self.webhook_method,
+                    body: self.webhook_body.clone(),
+                    state_url: state_url.clone(),
+                    state_pointer: self.webhook_state_pointer.clone(),
+                })
+            },
+        };
+
+        let plug = plug.verified().await;
+        bad_request_if(plug.is_err(), "Could not connect to plug, make sure a compatible device is reachable".to_string())?;
+        let plug = plug.unwrap();
+
+        log::info!("configured plug: {}", plug.describe());
+        Ok(plug)
+    }
+
+    fn plug_url(&self) -> WebResponse<String> {
+        self.plug_url.clone().ok_or_else(||
+            (StatusCode::BAD_REQUEST, String::from("plug_url is required for plug_kind shelly and tasmota")))
+    }
+}
+
+/// response for an error while communicating with a plug
+fn plug_error(plug: &Plug, error: Error) -> (StatusCode, String) {
+    let message = match error {
+        Error::SendingRequest => format!("Error while sending request to plug, make sure {} is available", plug.describe()),
+        Error::UnexpectedStatusCode(code) => format!("Plug unexpectedly responded with HTTP status code {code}"),
+        Error::UnexpectedResponse => String::from("Plug response did not have the expected structure"),
+        Error::NoState => String::from("Plug did not report its power state yet"),
+        Error::Superseded => return (StatusCode::CONFLICT, String::from("Command was superseded by a newer one for the same plug")),
+    };
+    (StatusCode::BAD_GATEWAY, message)
+}
@@ -0,0 +1,40 @@
+use axum::{extract, Json, http::StatusCode};
+
+use crate::plug::PowerSwitch;
+use crate::state::StateWrapper;
+use crate::api::{WebResponse, enclosure, enclosures::plug::plug_error};
+
+// as json response
+#[derive(utoipa::ToSchema, serde::Serialize)]
+pub struct GetPlugPowerResponse {
+    /// Whether the plug is on (`true`) or off (`false`)
+    power: bool,
+}
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/plug/{name}/power",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug")),
+    responses(
+        (status = 200, description = "Got plugs power state (`true` meaning \"on\" and `false` meaning \"off\")", body = GetPlugPowerResponse),
+        (status = 404, description = "Enclosure not yet configured or no plug with given name"),
+        (status = 502, description = "Unexpected response from plug"),
+    ),
+)]
+pub async fn get_plug_power(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+) -> WebResponse<Json<GetPlugPowerResponse>> {
+    let enclosures = state.lock().await;
+    let Some(device) = enclosure(&enclosures, &id)?.plugs.get(&name) else {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    };
+    // don't wait on the network while holding the lock
+    let plug = device.plug.clone();
+    drop(enclosures);
+
+    match plug.get_power().await {
+        Ok(power) => Ok(Json(GetPlugPowerResponse { power })),
+        Err(error) => Err(plug_error(&plug, error)),
+    }
+}
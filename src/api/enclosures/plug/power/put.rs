@@ -0,0 +1,99 @@
+use axum::{extract, http::StatusCode};
+use chrono::TimeDelta;
+use std::sync::Arc;
+
+use crate::time;
+use crate::device::PowerOverride;
+use crate::actuator::{Actuator, PlugId};
+use crate::scheduler::{self, Scheduler};
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, bad_request_if, enclosure_mut, enclosures::plug::plug_error};
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutPlugPowerQuery {
+    /// Whether to turn the plug on (`true`) or off (`false`)
+    power: bool,
+
+    /// Minutes to keep the plug in this state before resuming its schedule.
+    /// Without this or `until_next_transition`, the next transition of the schedule switches it as usual.
+    #[param(minimum = 1, example = 120)]
+    duration_minutes: Option<u32>,
+
+    /// Whether to keep the plug in this state until the next transition of its schedule,
+    /// even if the power state is enforced periodically
+    #[serde(default)]
+    #[param(default = false)]
+    until_next_transition: bool,
+}
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/plug/{name}/power",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug"), PutPlugPowerQuery),
+    responses(
+        (status = 200, description = "Successfully set plugs power state, replacing any previous override"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 404, description = "Enclosure not yet configured or no plug with given name"),
+        (status = 409, description = "Command was superseded by a newer one for the same plug"),
+        (status = 502, description = "Unexpected response from plug"),
+    ),
+)]
+pub async fn put_plug_power(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(actuator): extract::State<Actuator>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+    extract::Query(query): extract::Query<PutPlugPowerQuery>
+) -> WebResponse<String> {
+    bad_request_if(query.duration_minutes.is_some() && query.until_next_transition,
+        "Only one of duration_minutes and until_next_transition may be given".to_string())?;
+    bad_request_if(query.duration_minutes == Some(0), "duration_minutes must be at least 1".to_string())?;
+
+    let mut enclosures = state.lock().await;
+    let state_of_enclosure = enclosure_mut(&mut enclosures, &id)?;
+    let Some(device) = state_of_enclosure.plugs.get_mut(&name) else {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    };
+
+    let now = time::now();
+    let until = if query.until_next_transition {
+        let next_transition = state_of_enclosure.automation.schedule_of(device).and_then(|schedule|
+            scheduler::next_transition_of(&schedule, &state_of_enclosure.year_timer, state_of_enclosure.timezone, now));
+        let Some(until) = next_transition else {
+            return Err((StatusCode::BAD_REQUEST, format!("Plug \"{name}\" has no transition within the next two days")));
+        };
+        Some(until)
+    } else {
+        query.duration_minutes.map(|minutes| now + TimeDelta::minutes(minutes.into()))
+    };
+    let power_override = until.map(|until| PowerOverride { power: query.power, until });
+
+    // store the override and send the command under the lock, so that the scheduler either
+    // respects the override or sent its command before, which is superseded by this one then
+    let previous_override = std::mem::replace(&mut device.power_override, power_override);
+    let plug = device.plug.clone();
+    let switched = actuator.switch_once(PlugId { enclosure: id.clone(), name: name.clone() }, plug.clone(), query.power);
+    // don't wait on the network while holding the lock
+    drop(enclosures);
+    scheduler.reschedule();
+
+    if let Err(error) = switched.await {
+        // restore the previous override, unless the plug was reconfigured or removed meanwhile
+        let mut enclosures = state.lock().await;
+        if let Some(device) = enclosures.get_mut(&id).and_then(|state| state.plugs.get_mut(&name))
+            && device.power_override == power_override {
+            device.power_override = previous_override;
+        }
+        drop(enclosures);
+        scheduler.reschedule();
+        return Err(plug_error(&plug, error));
+    }
+    State::write_to_file(Arc::clone(&state));
+
+    let turn = if query.power { "on" } else { "off" };
+    Ok(match power_override {
+        Some(power_override) => format!("Successfully turned plug \"{name}\" {turn} until {}", power_override.until),
+        None => format!("Successfully turned plug \"{name}\" {turn}"),
+    })
+}
@@ -0,0 +1,53 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+
+use crate::time::Time;
+use crate::actuator::{Actuator, PlugId};
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, enclosure_mut};
+
+#[utoipa::path(
+    delete, path = "/enclosures/{id}/plug/{name}/power_override",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug")),
+    responses(
+        (status = 200, description = "Successfully cancelled override, switching the plug according to its schedule"),
+        (status = 404, description = "Enclosure not yet configured, no plug with given name or no override"),
+    ),
+)]
+pub async fn delete_plug_power_override(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(actuator): extract::State<Actuator>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+) -> WebResponse<String> {
+    let mut enclosures = state.lock().await;
+    let state_of_enclosure = enclosure_mut(&mut enclosures, &id)?;
+    let timezone = state_of_enclosure.timezone;
+    let year_timer = &state_of_enclosure.year_timer;
+    let Some(device) = state_of_enclosure.plugs.get_mut(&name) else {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    };
+    if device.power_override.take().is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("No override of plug \"{name}\"")));
+    }
+
+    let plug_id = PlugId { enclosure: id, name: name.clone() };
+    log::info!("cancelled override of plug {plug_id}");
+
+    // resume schedule right away instead of at its next transition, unless automation is paused
+    let power = state_of_enclosure.automation.schedule_of(device)
+        .map(|schedule| schedule.is_on_at(year_timer, Time::today(timezone), Time::now(timezone)));
+    if let Some(power) = power {
+        actuator.switch(plug_id, device.plug.clone(), device.retry_policy, power);
+    }
+    drop(enclosures);
+
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(match power {
+        Some(power) => format!("Successfully cancelled override of plug \"{name}\", turning it {}", if power { "on" } else { "off" }),
+        None => format!("Successfully cancelled override of plug \"{name}\""),
+    })
+}
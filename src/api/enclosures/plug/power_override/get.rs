@@ -0,0 +1,30 @@
+use axum::{extract, Json, http::StatusCode};
+
+use crate::time;
+use crate::device::PowerOverride;
+use crate::state::StateWrapper;
+use crate::api::{WebResponse, enclosure};
+
+#[utoipa::path(
+    get, path = "/enclosures/{id}/plug/{name}/power_override",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug")),
+    responses(
+        (status = 200, description = "Got power state the plug is kept in and when its schedule resumes", body = PowerOverride),
+        (status = 404, description = "Enclosure not yet configured, no plug with given name or no active override"),
+    ),
+)]
+pub async fn get_plug_power_override(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+) -> WebResponse<Json<PowerOverride>> {
+    let enclosures = state.lock().await;
+    let Some(device) = enclosure(&enclosures, &id)?.plugs.get(&name) else {
+        return Err((StatusCode::NOT_FOUND, format!("No plug named \"{name}\"")));
+    };
+    // might not be removed by the scheduler yet after expiring
+    match device.power_override.filter(|power_override| time::now() < power_override.until) {
+        Some(power_override) => Ok(Json(power_override)),
+        None => Err((StatusCode::NOT_FOUND, format!("No active override of plug \"{name}\""))),
+    }
+}
@@ -0,0 +1,167 @@
+use axum::{extract, http::StatusCode};
+use std::sync::Arc;
+
+use crate::retry;
+use crate::timer::day;
+use crate::device::{Device, Schedule};
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper};
+use crate::api::{WebResponse, bad_request_if, enclosure, enclosure_mut, parse_timer, parse_time, enclosures::plug::PlugQuery};
+
+/// kind of schedule, see [`Schedule`]
+#[derive(Debug, Clone, Copy, Default, utoipa::ToSchema, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleKind {
+    /// Follow the computed timers of the configuration
+    #[default]
+    Computed,
+    /// Turn on at `on_time` and off at `off_time` (or according to `intervals`) every day
+    Fixed,
+    /// Centered window covering `window_fraction` of the computed photoperiod, e.g. for UV lamps
+    Window,
+    /// Computed photoperiod extended by `lead_minutes` and `lag_minutes`, e.g. for basking lamps
+    Extended,
+    /// On exactly while the computed photoperiod is over, e.g. for night heaters
+    Inverted,
+}
+
+// from query parameters
+#[derive(utoipa::IntoParams, serde::Deserialize)]
+pub struct PutPlugQuery {
+    /// Timers the plug follows
+    #[serde(default)]
+    #[param(inline)]
+    schedule: ScheduleKind,
+
+    /// Time to turn the plug on every day (`HH:MM`), required for schedule `fixed`
+    #[param(example = "08:00")]
+    on_time: Option<String>,
+
+    /// Time to turn the plug off every day (`HH:MM`), required for schedule `fixed`
+    #[param(example = "18:00")]
+    off_time: Option<String>,
+
+    /// Multiple intervals to turn the plug on/off every day (`HH:MM-HH:MM`, comma-separated),
+    /// for schedule `fixed` instead of `on_time` and `off_time`.
+    /// Need to be ordered and not overlap, only the last one might span midnight.
+    #[param(example = "08:00-12:30,14:00-20:00")]
+    intervals: Option<String>,
+
+    /// Fraction of the computed photoperiod to turn the plug on for, required for schedule `window`
+    #[param(exclusive_minimum = 0.0, maximum = 1.0, example = 0.5)]
+    window_fraction: Option<f32>,
+
+    /// Minutes to turn the plug on before the computed photoperiod starts, for schedule `extended`
+    #[serde(default)]
+    #[param(default = 0, example = 30)]
+    lead_minutes: u16,
+
+    /// Minutes to turn the plug off after the computed photoperiod ends, for schedule `extended`
+    #[serde(default)]
+    #[param(default = 0, example = 60)]
+    lag_minutes: u16,
+
+    /// Maximum number of retries after the first attempt of a scheduled command failed
+    #[serde(default = "default_retry_max_retries")]
+    #[param(default = 35)]
+    retry_max_retries: u16,
+
+    /// How the interval between retries increases
+    #[serde(default = "default_retry_backoff")]
+    #[param(inline, default = "linear")]
+    retry_backoff: retry::Backoff,
+
+    /// Seconds to wait before the first retry
+    #[serde(default = "default_retry_initial_interval_seconds")]
+    #[param(default = 6)]
+    retry_initial_interval_seconds: u32,
+
+    /// Maximum seconds to wait between retries
+    #[serde(default = "default_retry_max_interval_seconds")]
+    #[param(default = 60)]
+    retry_max_interval_seconds: u32,
+
+    /// Seconds after the first attempt to give up retrying, regardless of `retry_max_retries`
+    #[param(example = 1800)]
+    retry_deadline_seconds: Option<u32>,
+
+    /// Fraction of every interval between retries to randomly add or subtract
+    #[serde(default)]
+    #[param(minimum = 0.0, maximum = 1.0, default = 0.0)]
+    retry_jitter: f32,
+}
+
+fn default_retry_max_retries() -> u16 { retry::Policy::default().max_retries }
+fn default_retry_backoff() -> retry::Backoff { retry::Policy::default().backoff }
+fn default_retry_initial_interval_seconds() -> u32 { retry::Policy::default().initial_interval_seconds }
+fn default_retry_max_interval_seconds() -> u32 { retry::Policy::default().max_interval_seconds }
+
+#[utoipa::path(
+    put, path = "/enclosures/{id}/plug/{name}",
+    tag = "Plug",
+    params(("id" = String, Path, description = "Id of the enclosure"), ("name" = String, Path, description = "Name of the plug"), PutPlugQuery, PlugQuery),
+    responses(
+        (status = 200, description = "Successfully configured plug"),
+        (status = 400, description = "Query parameters did not match expected structure"),
+        (status = 404, description = "Enclosure not yet configured"),
+    ),
+)]
+pub async fn put_plug(
+    extract::State(state): extract::State<StateWrapper>,
+    extract::State(scheduler): extract::State<Scheduler>,
+    extract::Path((id, name)): extract::Path<(String, String)>,
+    extract::Query(query): extract::Query<PutPlugQuery>,
+    extract::Query(plug_query): extract::Query<PlugQuery>,
+) -> WebResponse<String> {
+    enclosure(&*state.lock().await, &id)?;
+
+    let schedule = match (query.schedule, query.intervals.as_deref()) {
+        (ScheduleKind::Computed, _) => Schedule::Computed,
+        (ScheduleKind::Fixed, Some(intervals)) => Schedule::Fixed { timer: parse_timer(intervals)? },
+        (ScheduleKind::Fixed, None) => {
+            let required = |name| (StatusCode::BAD_REQUEST, format!("{name} is required for schedule fixed"));
+            let on_time = parse_time(query.on_time.as_deref().ok_or_else(|| required("on_time"))?, "on_time")?;
+            let off_time = parse_time(query.off_time.as_deref().ok_or_else(|| required("off_time"))?, "off_time")?;
+            bad_request_if(on_time == off_time, "on_time and off_time must not be equal".to_string())?;
+            Schedule::Fixed { timer: day::Timer::new(on_time, off_time) }
+        },
+        (ScheduleKind::Window, _) => {
+            let Some(fraction) = query.window_fraction else {
+                return Err((StatusCode::BAD_REQUEST, String::from("window_fraction is required for schedule window")));
+            };
+            bad_request_if(fraction <= 0. || fraction > 1., "window_fraction must be between 0.0 (exclusive) and 1.0".to_string())?;
+            Schedule::Window { fraction }
+        },
+        (ScheduleKind::Extended, _) => {
+            bad_request_if(query.lead_minutes >= 24 * 60 || query.lag_minutes >= 24 * 60,
+                "lead_minutes and lag_minutes must be less than a day".to_string())?;
+            Schedule::Extended { lead_minutes: query.lead_minutes, lag_minutes: query.lag_minutes }
+        },
+        (ScheduleKind::Inverted, _) => Schedule::Inverted,
+    };
+
+    bad_request_if(!(0. ..= 1.).contains(&query.retry_jitter), "retry_jitter must be between 0.0 and 1.0".to_string())?;
+    let retry_policy = retry::Policy {
+        max_retries: query.retry_max_retries,
+        backoff: query.retry_backoff,
+        initial_interval_seconds: query.retry_initial_interval_seconds,
+        max_interval_seconds: query.retry_max_interval_seconds,
+        deadline_seconds: query.retry_deadline_seconds,
+        jitter: query.retry_jitter,
+    };
+
+    // might take a while, so don't hold the lock meanwhile
+    let plug = plug_query.plug().await?;
+
+    let mut enclosures = state.lock().await;
+    let plugs = &mut enclosure_mut(&mut enclosures, &id)?.plugs;
+    // an active override outlasts reconfiguring the plug
+    let power_override = plugs.get(&name).and_then(|device| device.power_override);
+    plugs.insert(name.clone(), Device { plug, schedule, retry_policy, power_override });
+    drop(enclosures);
+
+    log::info!("configured plug {name}");
+    State::write_to_file(Arc::clone(&state));
+    scheduler.reschedule();
+    Ok(format!("Successfully configured plug \"{name}\""))
+}
@@ -1,20 +1,46 @@
 #![allow(clippy::module_name_repetitions)]
 
-pub mod configuration;
-pub mod plug;
+pub mod enclosures;
 
 use utoipa::OpenApi;
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 use utoipa_swagger_ui::SwaggerUi;
 use std::{sync::Arc, net::{SocketAddr, IpAddr, Ipv4Addr}};
-use axum::{response::Redirect, routing::{get, put}, http::{header, StatusCode, Method}};
+use axum::{response::Redirect, routing::{get, put, delete}, http::{header, StatusCode, Method}};
 
+use crate::time::Time;
+use crate::timer::day;
 use crate::constants::PORT;
-use crate::state::StateWrapper;
+use crate::actuator::Actuator;
+use crate::scheduler::Scheduler;
+use crate::state::{State, StateWrapper, Enclosures};
+use enclosures::{configuration, plug};
 
 pub type WebResponse<T> = Result<T, (StatusCode, String)>;
 
+/// shared by all handlers, which can extract single fields
+#[derive(Clone, axum::extract::FromRef)]
+struct AppState {
+    state: StateWrapper,
+    actuator: Actuator,
+    scheduler: Scheduler,
+}
+
+/// configured enclosure with given id
+fn enclosure<'a>(enclosures: &'a Enclosures, id: &str) -> WebResponse<&'a State> {
+    enclosures.get(id).ok_or_else(|| not_configured(id))
+}
+
+/// configured enclosure with given id
+fn enclosure_mut<'a>(enclosures: &'a mut Enclosures, id: &str) -> WebResponse<&'a mut State> {
+    enclosures.get_mut(id).ok_or_else(|| not_configured(id))
+}
+
+fn not_configured(id: &str) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("Enclosure \"{id}\" not yet configured, consider calling /enclosures/{id}/configuration first"))
+}
+
 fn bad_request_if(condition: bool, message: String) -> WebResponse<()> {
     if condition {
         Err((StatusCode::BAD_REQUEST, message))
@@ -23,30 +49,95 @@ fn bad_request_if(condition: bool, message: String) -> WebResponse<()> {
     }
 }
 
+/// parse comma-separated intervals like "08:00-12:30,14:00-20:00"
+fn parse_timer(intervals: &str) -> WebResponse<day::Timer> {
+    let intervals = intervals.split(',')
+        .map(parse_interval)
+        .collect::<WebResponse<Vec<_>>>()?;
+    day::Timer::from_intervals(intervals).map_err(|error| (StatusCode::BAD_REQUEST, error))
+}
+
+/// parse time of day like "08:00" of query parameter with given name
+fn parse_time(time: &str, name: &str) -> WebResponse<Time> {
+    match Time::from_hhmmss(time) {
+        Ok(time) if time.is_valid() => Ok(time),
+        _ => Err((StatusCode::BAD_REQUEST, format!("{name} must be a time of day like \"08:00\""))),
+    }
+}
+
+/// parse interval like "08:00-12:30"
+fn parse_interval(interval: &str) -> WebResponse<day::Interval> {
+    let error = || (StatusCode::BAD_REQUEST, format!("intervals must be like \"08:00-12:30\", got \"{interval}\""));
+    let Some((on_time, off_time)) = interval.trim().split_once('-') else {
+        return Err(error());
+    };
+    let (Ok(on_time), Ok(off_time)) = (Time::from_hhmmss(on_time), Time::from_hhmmss(off_time)) else {
+        return Err(error());
+    };
+    if !on_time.is_valid() || !off_time.is_valid() || on_time == off_time {
+        return Err(error());
+    }
+    Ok(day::Interval::new(on_time, off_time))
+}
+
 /// start webserver. never terminates.
-pub async fn start_server(state: StateWrapper) {
+pub async fn start_server(state: StateWrapper, actuator: Actuator, scheduler: Scheduler) {
     // set up utoipa swagger ui
     #[derive(OpenApi)]
     #[openapi(paths(
         // functions with #[utoipa::path(...)]
+        enclosures::get::get_enclosures,
+        enclosures::delete::delete_enclosure,
         configuration::get::get_configuration,
         configuration::put::put_configuration,
+        configuration::manual::put::put_manual_configuration,
         configuration::today::get::get_configuration_today,
+        configuration::dst::get::get_configuration_dst,
+        configuration::automation::put::put_automation,
+        configuration::brumation::get::get_brumation,
+        configuration::brumation::put::put_brumation,
+        configuration::brumation::delete::delete_brumation,
+        configuration::overrides::get::get_overrides,
+        configuration::overrides::get::get_override,
+        configuration::overrides::put::put_override,
+        configuration::overrides::delete::delete_override,
+        plug::get::get_plugs,
+        plug::put::put_plug,
+        plug::delete::delete_plug,
         plug::power::get::get_plug_power,
         plug::power::put::put_plug_power,
+        plug::power_override::get::get_plug_power_override,
+        plug::power_override::delete::delete_plug_power_override,
     ))]
     struct ApiDoc;
 
     // configure routes
     let app = axum::Router::new()
         // api routes
-        .route("/configuration", get(configuration::get::get_configuration))
-        .route("/configuration", put(configuration::put::put_configuration))
-        .route("/configuration/today", get(configuration::today::get::get_configuration_today))
-        .route("/plug/power", put(plug::power::put::put_plug_power))
-        .route("/plug/power", get(plug::power::get::get_plug_power))
+        .route("/enclosures", get(enclosures::get::get_enclosures))
+        .route("/enclosures/{id}", delete(enclosures::delete::delete_enclosure))
+        .route("/enclosures/{id}/configuration", get(configuration::get::get_configuration))
+        .route("/enclosures/{id}/configuration", put(configuration::put::put_configuration))
+        .route("/enclosures/{id}/configuration/manual", put(configuration::manual::put::put_manual_configuration))
+        .route("/enclosures/{id}/configuration/today", get(configuration::today::get::get_configuration_today))
+        .route("/enclosures/{id}/configuration/dst", get(configuration::dst::get::get_configuration_dst))
+        .route("/enclosures/{id}/configuration/automation", put(configuration::automation::put::put_automation))
+        .route("/enclosures/{id}/configuration/brumation", get(configuration::brumation::get::get_brumation))
+        .route("/enclosures/{id}/configuration/brumation", put(configuration::brumation::put::put_brumation))
+        .route("/enclosures/{id}/configuration/brumation", delete(configuration::brumation::delete::delete_brumation))
+        .route("/enclosures/{id}/configuration/overrides", get(configuration::overrides::get::get_overrides))
+        .route("/enclosures/{id}/configuration/overrides/{date}", get(configuration::overrides::get::get_override))
+        .route("/enclosures/{id}/configuration/overrides/{date}", put(configuration::overrides::put::put_override))
+        .route("/enclosures/{id}/configuration/overrides/{date}", delete(configuration::overrides::delete::delete_override))
+        .route("/enclosures/{id}/plug", get(plug::get::get_plugs))
+        .route("/enclosures/{id}/plug/{name}", put(plug::put::put_plug))
+        .route("/enclosures/{id}/plug/{name}", delete(plug::delete::delete_plug))
+        .route("/enclosures/{id}/plug/{name}/power", put(plug::power::put::put_plug_power))
+        .route("/enclosures/{id}/plug/{name}/power", get(plug::power::get::get_plug_power))
+        .route("/enclosures/{id}/plug/{name}/power_override", get(plug::power_override::get::get_plug_power_override))
+        .route("/enclosures/{id}/plug/{name}/power_override", delete(plug::power_override::delete::delete_plug_power_override))
 
-        .with_state(Arc::clone(&state))
+        .with_state(AppState { state: Arc::clone(&state), actuator, scheduler })
 
         // allow CORS from frontend
         .layer(CorsLayer::new()
@@ -54,7 +145,7 @@ pub async fn start_server(state: StateWrapper) {
                 "http://localhost:4173".parse().unwrap(), // vite dev default
                 "http://localhost:5173".parse().unwrap(), // vite preview default
             ])
-            .allow_methods([Method::GET, Method::PUT])
+            .allow_methods([Method::GET, Method::PUT, Method::DELETE])
             .allow_headers([header::CONTENT_TYPE]))
 
         // temporarily redirect root to swagger ui
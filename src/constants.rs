@@ -24,10 +24,16 @@ cfg_if::cfg_if! {
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         pub const MILLISECONDS_PER_MINUTE: u16 = (MINUTES_PER_DAY / 24. * 1000.) as u16;
 
-        /// interval for checking if the current minute matches a timer
-        pub const CHECK_INTERVAL: Duration = Duration::from_millis((MILLISECONDS_PER_MINUTE / 2) as u64);
+        /// maximum time for the scheduler to sleep, to notice clock jumps (15min simulated)
+        pub const MAX_SLEEP_INTERVAL: Duration = Duration::from_millis((MILLISECONDS_PER_MINUTE * 15) as u64);
+
+        /// interval for comparing actual and expected power state of plugs (5min simulated)
+        pub const RECONCILE_INTERVAL: Duration = Duration::from_millis((MILLISECONDS_PER_MINUTE * 5) as u64);
     } else {
-        /// interval for checking if the current minute matches a timer
-        pub const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+        /// maximum time for the scheduler to sleep, to notice clock jumps and suspends
+        pub const MAX_SLEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+        /// interval for comparing actual and expected power state of plugs
+        pub const RECONCILE_INTERVAL: Duration = Duration::from_secs(5 * 60);
     }
 }
@@ -0,0 +1,238 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::retry;
+use crate::time::Time;
+use crate::plug::Plug;
+use crate::timer::{day, year};
+
+/// plug with the schedule it follows, one of possibly multiple in a terrarium
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Device {
+    pub plug: Plug,
+    pub schedule: Schedule,
+    /// for commands from the scheduler
+    #[serde(default)]
+    pub retry_policy: retry::Policy,
+    /// power state set by hand, taking precedence over the schedule until it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_override: Option<PowerOverride>,
+}
+
+/// power state to keep a plug in until a moment, after which its schedule is resumed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+pub struct PowerOverride {
+    /// Whether the plug is kept on (`true`) or off (`false`)
+    pub power: bool,
+    /// Moment to resume the schedule at
+    #[schema(value_type = String, example = "2025-06-21T20:00:00Z")]
+    pub until: DateTime<Utc>,
+}
+
+/// which timers a device follows.
+/// all but `Fixed` are derived from the computed timers (the photoperiod) of each day.
+#[derive(Debug, Clone, PartialEq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Schedule {
+    /// Computed timers of the configuration
+    Computed,
+    /// Same timer every day
+    Fixed {
+        timer: day::Timer,
+    },
+    /// Centered window covering a fraction of the photoperiod, e.g. for UV lamps
+    Window {
+        /// Between 0.0 (exclusive) and 1.0
+        #[schema(exclusive_minimum = 0.0, maximum = 1.0, example = 0.5)]
+        fraction: f32,
+    },
+    /// Photoperiod extended by some minutes on both ends, e.g. for basking lamps.
+    /// Limited to the borders of the day, or to all but one minute of it if the photoperiod spans midnight.
+    Extended {
+        /// Minutes to turn on before the photoperiod starts
+        #[schema(example = 30)]
+        lead_minutes: u16,
+        /// Minutes to turn off after the photoperiod ends
+        #[schema(example = 60)]
+        lag_minutes: u16,
+    },
+    /// On exactly while the photoperiod is over, e.g. for night heaters
+    Inverted,
+}
+
+impl Schedule {
+    /// timer to follow on a day with given computed timer
+    pub fn derive(&self, computed: &day::Timer) -> day::Timer {
+        let (Some(&on), Some(&off)) = (computed.on_time(), computed.off_time()) else {
+            // no photoperiod to derive from
+            return match self {
+                Self::Fixed { timer } => timer.clone(),
+                Self::Inverted => computed.inverted(),
+                Self::Computed | Self::Window { .. } | Self::Extended { .. } => day::Timer::off(),
+            };
+        };
+        match self {
+            Self::Computed => computed.clone(),
+            Self::Fixed { timer } => timer.clone(),
+            Self::Window { fraction } => {
+                let length = computed.length();
+                let center = ((length / 2.) + on).wrapped();
+                // at least one minute to keep on and off time apart
+                let half_length = (length * (fraction / 2.)).max(Time::new(0, 1));
+                day::Timer::new((center - half_length).wrapped(), (center + half_length).wrapped())
+            },
+            Self::Extended { lead_minutes, lag_minutes } => {
+                let day_start = Time::new(0, 0);
+                let day_end = Time::new(23, 59);
+                // limit to a day to avoid overflows
+                let lead = Time::from_minutes((*lead_minutes).min(24 * 60).try_into().unwrap());
+                let lag  = Time::from_minutes( (*lag_minutes).min(24 * 60).try_into().unwrap());
+                if !computed.spans_midnight() {
+                    return day::Timer::new(
+                        (on - lead).clamp(day_start, day_end),
+                        (off + lag).clamp(day_start, day_end),
+                    );
+                }
+                // no day borders to limit to, so keep at least a minute off for on and off time to differ
+                let remaining = day_end - computed.length();
+                let lead = lead.min(remaining);
+                let lag = lag.min(remaining - lead);
+                day::Timer::new((on - lead).wrapped(), (off + lag).wrapped())
+            },
+            Self::Inverted => computed.inverted(),
+        }
+    }
+
+    /// timer to follow on given date, given the computed `year_timer` of the configuration
+    pub fn day_timer(&self, year_timer: &year::Timer, date: NaiveDate) -> day::Timer {
+        self.derive(&year_timer.for_date(date))
+    }
+
+    /// timer in effect at given local date and time, together with the date it belongs to:
+    /// the one of the previous day while it lasts past midnight, otherwise the one of given date
+    pub fn active_day_timer(&self, year_timer: &year::Timer, date: NaiveDate, time: Time) -> (NaiveDate, day::Timer) {
+        if let Some(yesterday) = date.pred_opt() {
+            let timer = self.day_timer(year_timer, yesterday);
+            if timer.spans_midnight() && timer.off_time().is_some_and(|off_time| time < *off_time) {
+                return (yesterday, timer);
+            }
+        }
+        (date, self.day_timer(year_timer, date))
+    }
+
+    /// whether to be on at given local date and time
+    pub fn is_on_at(&self, year_timer: &year::Timer, date: NaiveDate, time: Time) -> bool {
+        let (timer_date, timer) = self.active_day_timer(year_timer, date, time);
+        timer_date != date || timer.covers(time)
+    }
+}
+
+impl Device {
+    pub fn new(plug: Plug, schedule: Schedule) -> Self {
+        Self { plug, schedule, retry_policy: retry::Policy::default(), power_override: None }
+    }
+
+    /// power state forced by an override that is still active at given moment
+    pub fn overridden_power(&self, moment: DateTime<Utc>) -> Option<bool> {
+        self.power_override
+            .filter(|power_override| moment < power_override.until)
+            .map(|power_override| power_override.power)
+    }
+
+    /// timer to follow on given date, given the computed `year_timer` of the configuration
+    pub fn day_timer(&self, year_timer: &year::Timer, date: NaiveDate) -> day::Timer {
+        self.schedule.day_timer(year_timer, date)
+    }
+
+    /// see [`Schedule::active_day_timer`]
+    pub fn active_day_timer(&self, year_timer: &year::Timer, date: NaiveDate, time: Time) -> (NaiveDate, day::Timer) {
+        self.schedule.active_day_timer(year_timer, date, time)
+    }
+
+    /// whether the plug is supposed to be on at given local date and time
+    pub fn is_on_at(&self, year_timer: &year::Timer, date: NaiveDate, time: Time) -> bool {
+        self.schedule.is_on_at(year_timer, date, time)
+    }
+
+    /// timers to follow on the 366 days starting on given date, given the computed `year_timer` of the configuration.
+    /// see [`year::Timer::upcoming_day_timers`]
+    pub fn day_timers(&self, year_timer: &year::Timer, from: NaiveDate) -> [day::Timer; 366] {
+        year_timer.upcoming_day_timers(from).each_ref().map(|day_timer| self.schedule.derive(day_timer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computed() -> day::Timer {
+        day::Timer::new(Time::new(8, 0), Time::new(20, 0))
+    }
+
+    #[test]
+    fn window() {
+        let timer = Schedule::Window { fraction: 0.5 }.derive(&computed());
+        assert_eq!(timer, day::Timer::new(Time::new(11, 0), Time::new(17, 0)));
+    }
+
+    #[test]
+    fn extended() {
+        let timer = Schedule::Extended { lead_minutes: 30, lag_minutes: 60 }.derive(&computed());
+        assert_eq!(timer, day::Timer::new(Time::new(7, 30), Time::new(21, 0)));
+    }
+
+    #[test]
+    fn extended_to_day_borders() {
+        let timer = Schedule::Extended { lead_minutes: 600, lag_minutes: 600 }.derive(&computed());
+        assert_eq!(timer, day::Timer::new(Time::new(0, 0), Time::new(23, 59)));
+    }
+
+    #[test]
+    fn extended_over_midnight() {
+        let computed = day::Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let timer = Schedule::Extended { lead_minutes: 60, lag_minutes: 120 }.derive(&computed);
+        assert_eq!(timer, day::Timer::new(Time::new(19, 0), Time::new(6, 0)));
+        // lead is kept, lag is limited to keep a minute off
+        let timer = Schedule::Extended { lead_minutes: 600, lag_minutes: 600 }.derive(&computed);
+        assert_eq!(timer, day::Timer::new(Time::new(10, 0), Time::new(9, 59)));
+    }
+
+    #[test]
+    fn window_over_midnight() {
+        let computed = day::Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let timer = Schedule::Window { fraction: 0.5 }.derive(&computed);
+        assert_eq!(timer, day::Timer::new(Time::new(22, 0), Time::new(2, 0)));
+    }
+
+    #[test]
+    fn over_midnight() {
+        // moonlight on until 04:00 of the next day, except on the first day
+        let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let timer = day::Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let device = Device::new(Plug::tasmota(String::new()), Schedule::Fixed { timer: timer.clone() });
+        let year_timer = year::Timer::new(std::array::from_fn(|_| computed()));
+
+        assert_eq!(device.active_day_timer(&year_timer, date, Time::new(2, 0)), (date.pred_opt().unwrap(), timer.clone()));
+        assert_eq!(device.active_day_timer(&year_timer, date, Time::new(4, 0)), (date, timer));
+        assert!(device.is_on_at(&year_timer, date, Time::new(2, 0)));
+        assert!(!device.is_on_at(&year_timer, date, Time::new(4, 0)));
+        assert!(!device.is_on_at(&year_timer, date, Time::new(19, 59)));
+        assert!(device.is_on_at(&year_timer, date, Time::new(23, 0)));
+    }
+
+    #[test]
+    fn overridden_power() {
+        let until = DateTime::from_timestamp(1_750_528_800, 0).unwrap();
+        let mut device = Device::new(Plug::tasmota(String::new()), Schedule::Computed);
+        assert_eq!(device.overridden_power(until), None);
+
+        device.power_override = Some(PowerOverride { power: true, until });
+        assert_eq!(device.overridden_power(until - chrono::TimeDelta::seconds(1)), Some(true));
+        assert_eq!(device.overridden_power(until), None);
+    }
+
+    #[test]
+    fn inverted() {
+        let timer = Schedule::Inverted.derive(&computed());
+        assert_eq!(timer, day::Timer::new(Time::new(20, 0), Time::new(8, 0)));
+    }
+}
@@ -1,17 +1,22 @@
 mod api;
+mod actuator;
 mod timer;
 mod constants;
+mod device;
 mod plug;
 mod state;
 mod sunrise_api;
+mod retry;
+mod reconcile;
+mod scheduler;
 mod time;
 
 use tokio::sync::Mutex;
 use std::sync::Arc;
 
-use time::Time;
 use state::State;
-use constants::CHECK_INTERVAL;
+use actuator::Actuator;
+use scheduler::Scheduler;
 
 #[tokio::main]
 async fn main() {
@@ -35,42 +40,14 @@ async fn main() {
     // thread-safe state (persisted with json file)
     let state = Arc::new(Mutex::new(State::read_from_file()));
 
-    // to avoid matching timers more than once per minute
-    let mut last_checked_time = None;
+    // switches plugs without blocking the scheduler or the api
+    let actuator = Actuator::spawn();
 
-    // start webserver ("fire and forget" instead of "await")
-    tokio::spawn(api::start_server(Arc::clone(&state)));
-
-    loop {
-        #[allow(clippy::significant_drop_in_scrutinee)]
-        if let Some(ref state) = *state.lock().await {
-            log::trace!("checking for new minute");
-
-            let now = Time::now(state.timezone);
-            if last_checked_time != Some(now) {
-                if cfg!(feature = "demo_mode") && now.minute() % 15 == 0 {
-                    log::info!("it is {now}");
-                } else {
-                    log::trace!("new minute detected");
-                }
-
-                let day_timer = state.year_timer.for_today(state.timezone);
-                if now == *day_timer.on_time() {
-                    log::info!("matched timer for {now}, turning plug on");
-                    state.plug.set_power_with_retry(true).await;
-                } else if now == *day_timer.off_time() {
-                    log::info!("matched timer for {now}, turning plug off");
-                    state.plug.set_power_with_retry(false).await;
-                } else {
-                    log::trace!("no timer matched");
-                }
+    // switches plugs at the transitions of their timers
+    let scheduler = Scheduler::new();
 
-                last_checked_time = Some(now);
-            }
-        } else {
-            log::trace!("nothing to check, no timers configured");
-        }
+    // start webserver ("fire and forget" instead of "await")
+    tokio::spawn(api::start_server(Arc::clone(&state), actuator.clone(), scheduler.clone()));
 
-        tokio::time::sleep(CHECK_INTERVAL).await;
-    }
+    scheduler.run(state, actuator).await;
 }
@@ -0,0 +1,213 @@
+//! devices that can be switched on/off, like smart plugs
+
+mod shelly_gen1;
+mod shelly_gen2;
+mod tasmota;
+mod mqtt;
+mod webhook;
+
+pub use mqtt::Config as MqttConfig;
+pub use webhook::{Config as WebhookConfig, Method as WebhookMethod};
+
+use reqwest::StatusCode;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Error {
+    /// e.g. when url can't be reached
+    SendingRequest,
+    UnexpectedStatusCode(StatusCode),
+    /// response did not have the expected structure
+    UnexpectedResponse,
+    /// plug did not report its power state yet
+    NoState,
+    /// a newer command for the same plug was issued before this one completed
+    Superseded,
+}
+
+/// backend-independent interface of a device that can be switched on/off
+pub trait PowerSwitch {
+    async fn set_power(&self, power: bool) -> Result<(), Error>;
+
+    async fn get_power(&self) -> Result<bool, Error>;
+
+    /// human-readable description of the device, e.g. for error messages
+    fn describe(&self) -> String;
+}
+
+/// plug to control, (de)serialized with a `kind` field to tell backends apart
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Self", tag = "kind", rename_all = "snake_case")]
+pub enum Plug {
+    ShellyGen1(shelly_gen1::Plug),
+    ShellyGen2(shelly_gen2::Plug),
+    Tasmota(tasmota::Plug),
+    Mqtt(Box<mqtt::Plug>),
+    Webhook(webhook::Plug),
+}
+
+/// kind of plug to configure
+#[derive(Debug, Clone, Copy, Default, utoipa::ToSchema, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Shelly plug of any generation, detected automatically
+    #[default]
+    Shelly,
+    /// Plug running Tasmota
+    Tasmota,
+    /// Plug controlled via MQTT broker, e.g. using Zigbee2MQTT or Tasmota MQTT
+    Mqtt,
+    /// Plug controlled via configurable HTTP requests, e.g. running ESPHome or custom firmware
+    Webhook,
+}
+
+impl serde::Serialize for Plug {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Plug {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        // plugs from before multiple backends were supported have no kind and are always shelly gen1
+        if let serde_json::Value::Object(fields) = &mut value
+            && !fields.contains_key("kind") {
+            fields.insert(String::from("kind"), serde_json::Value::from("shelly_gen1"));
+        }
+        Self::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Plug {
+    /// shelly plug of generation detected behind given url
+    pub async fn shelly(base_url: String) -> Result<Self, Error> {
+        Ok(if Self::shelly_generation(&base_url).await? >= 2 {
+            Self::ShellyGen2(shelly_gen2::Plug::new(base_url))
+        } else {
+            Self::ShellyGen1(shelly_gen1::Plug::new(base_url))
+        })
+    }
+
+    pub const fn tasmota(base_url: String) -> Self {
+        Self::Tasmota(tasmota::Plug::new(base_url))
+    }
+
+    pub fn mqtt(config: MqttConfig) -> Self {
+        Self::Mqtt(Box::new(mqtt::Plug::new(config)))
+    }
+
+    pub fn webhook(config: WebhookConfig) -> Self {
+        Self::Webhook(webhook::Plug::new(config))
+    }
+
+    /// tests plug by attempting to get power status.
+    /// mqtt plugs only need to connect to their broker, as they might not have reported a state yet.
+    pub async fn verified(self) -> Result<Self, Error> {
+        match self {
+            Self::Mqtt(ref plug) if !cfg!(feature = "mock_plug") => plug.connect().await?,
+            _ => { self.get_power().await?; },
+        }
+        Ok(self)
+    }
+
+    /// get generation of shelly device from its `/shelly` endpoint,
+    /// which is available on every generation. gen1 devices don't report one.
+    async fn shelly_generation(base_url: &str) -> Result<u64, Error> {
+        if cfg!(feature = "mock_plug") {
+            log::debug!("mocking plug response: plug is shelly gen1");
+            return Ok(1)
+        }
+
+        let url = format!("{base_url}/shelly");
+        log::debug!("requesting url: {url}");
+
+        let response = reqwest::get(url).await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        let response = response.unwrap();
+        match response.status() {
+            StatusCode::OK => {
+                let json = response.json::<serde_json::Value>().await
+                    .map_err(|_| Error::UnexpectedResponse)?;
+                let generation = json["gen"].as_u64().unwrap_or(1);
+                log::debug!("detected shelly gen{generation} device");
+                Ok(generation)
+            },
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+
+    pub fn get_url(&self) -> String {
+        match self {
+            Self::ShellyGen1(plug) => plug.get_url().to_string(),
+            Self::ShellyGen2(plug) => plug.get_url().to_string(),
+            Self::Tasmota(plug) => plug.get_url().to_string(),
+            Self::Mqtt(plug) => plug.get_url(),
+            Self::Webhook(plug) => plug.get_url().to_string(),
+        }
+    }
+}
+
+impl PowerSwitch for Plug {
+    async fn set_power(&self, power: bool) -> Result<(), Error> {
+        if cfg!(feature = "mock_plug") {
+            log::debug!("mocking plug response: turning plug {}", if power { "on" } else { "off" });
+            return Ok(())
+        }
+
+        match self {
+            Self::ShellyGen1(plug) => plug.set_power(power).await,
+            Self::ShellyGen2(plug) => plug.set_power(power).await,
+            Self::Tasmota(plug) => plug.set_power(power).await,
+            Self::Mqtt(plug) => plug.set_power(power).await,
+            Self::Webhook(plug) => plug.set_power(power).await,
+        }
+    }
+
+    async fn get_power(&self) -> Result<bool, Error> {
+        if cfg!(feature = "mock_plug") {
+            log::debug!("mocking plug response: plug is off");
+            return Ok(false)
+        }
+
+        match self {
+            Self::ShellyGen1(plug) => plug.get_power().await,
+            Self::ShellyGen2(plug) => plug.get_power().await,
+            Self::Tasmota(plug) => plug.get_power().await,
+            Self::Mqtt(plug) => plug.get_power().await,
+            Self::Webhook(plug) => plug.get_power().await,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::ShellyGen1(plug) => plug.describe(),
+            Self::ShellyGen2(plug) => plug.describe(),
+            Self::Tasmota(plug) => plug.describe(),
+            Self::Mqtt(plug) => plug.describe(),
+            Self::Webhook(plug) => plug.describe(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Plug, shelly_gen1};
+
+    #[test]
+    fn deserialize_without_kind() {
+        let plug = serde_json::from_str::<Plug>(r#"{"base_url":"http://192.168.0.2"}"#).unwrap();
+        assert!(matches!(plug, Plug::ShellyGen1(_)));
+        assert_eq!(plug.get_url(), "http://192.168.0.2");
+    }
+
+    #[test]
+    fn roundtrip() {
+        let plug = Plug::ShellyGen1(shelly_gen1::Plug::new(String::from("http://192.168.0.3")));
+        let json = serde_json::to_string(&plug).unwrap();
+        assert!(json.contains(r#""kind":"shelly_gen1""#));
+        assert!(matches!(serde_json::from_str::<Plug>(&json).unwrap(), Plug::ShellyGen1(_)));
+    }
+}
@@ -0,0 +1,250 @@
+//! for smart plugs controlled via an MQTT broker, e.g. using Zigbee2MQTT or Tasmota MQTT
+
+use std::sync::{Arc, Weak, Mutex};
+use std::time::Duration;
+use tokio::sync::{OnceCell, watch};
+use rumqttc::{AsyncClient, MqttOptions, QoS, Event, Packet};
+
+use super::{Error, PowerSwitch};
+
+/// how long to wait for the broker to accept a new connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// how long to wait before reconnecting after losing the connection to the broker
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::struct_field_names)]
+pub struct Plug {
+    /// hostname or ip address of broker
+    broker_host: String,
+    broker_port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    /// topic to publish `payload_on` / `payload_off` to
+    command_topic: String,
+    /// topic the plug publishes its state to
+    state_topic: String,
+    /// payload to turn the plug on, e.g. `ON` or `{"state":"ON"}`
+    payload_on: String,
+    /// payload to turn the plug off, e.g. `OFF` or `{"state":"OFF"}`
+    payload_off: String,
+    /// JSON pointer to the state inside a JSON payload on `state_topic`, e.g. `/state`.
+    /// if `None`, the whole payload is used as the state.
+    state_pointer: Option<String>,
+    /// state meaning the plug is on, e.g. `ON`
+    state_on: String,
+    /// state meaning the plug is off, e.g. `OFF`
+    state_off: String,
+
+    /// established lazily on first use, shared between clones
+    #[serde(skip)]
+    connection: Arc<OnceCell<Connection>>,
+}
+
+/// configuration of an mqtt plug, for [`Plug::new`]
+#[allow(clippy::struct_field_names)]
+pub struct Config {
+    pub broker_host: String,
+    pub broker_port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub command_topic: String,
+    pub state_topic: String,
+    pub payload_on: String,
+    pub payload_off: String,
+    pub state_pointer: Option<String>,
+    pub state_on: String,
+    pub state_off: String,
+}
+
+#[derive(Debug)]
+struct Connection {
+    client: AsyncClient,
+    /// last state received on state topic, `None` if nothing was received yet
+    power: Arc<Mutex<Option<bool>>>,
+    /// whether the broker currently accepts our connection
+    connected: watch::Receiver<bool>,
+}
+
+impl Plug {
+    pub fn new(config: Config) -> Self {
+        Self {
+            broker_host: config.broker_host,
+            broker_port: config.broker_port,
+            username: config.username,
+            password: config.password,
+            command_topic: config.command_topic,
+            state_topic: config.state_topic,
+            payload_on: config.payload_on,
+            payload_off: config.payload_off,
+            state_pointer: config.state_pointer,
+            state_on: config.state_on,
+            state_off: config.state_off,
+            connection: Arc::default(),
+        }
+    }
+
+    pub fn get_url(&self) -> String {
+        format!("mqtt://{}:{}", self.broker_host, self.broker_port)
+    }
+
+    /// connect to broker and wait until it accepted the connection
+    pub async fn connect(&self) -> Result<(), Error> {
+        let mut connected = self.connection().await.connected.clone();
+        match tokio::time::timeout(CONNECT_TIMEOUT, connected.wait_for(|connected| *connected)).await {
+            Ok(Ok(_)) => Ok(()),
+            _ => Err(Error::SendingRequest),
+        }
+    }
+
+    async fn connection(&self) -> &Connection {
+        self.connection.get_or_init(|| async { self.spawn_connection() }).await
+    }
+
+    /// spawn task polling the connection until all clones of this plug are dropped
+    fn spawn_connection(&self) -> Connection {
+        // unique enough to not collide with other instances of this backend
+        let client_id = format!("terralux-backend-{}-{}", std::process::id(),
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().subsec_nanos());
+        let mut options = MqttOptions::new(client_id, &self.broker_host, self.broker_port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(ref username) = self.username {
+            options.set_credentials(username, self.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut event_loop) = AsyncClient::new(options, 10);
+        let power = Arc::new(Mutex::new(None));
+        let (connected_sender, connected) = watch::channel(false);
+
+        let task_client = client.clone();
+        let task_power = Arc::downgrade(&power);
+        let plug = self.clone_config();
+        tokio::spawn(async move {
+            loop {
+                let event = event_loop.poll().await;
+                let Some(power) = Weak::upgrade(&task_power) else {
+                    log::debug!("mqtt plug was dropped, closing connection to {}", plug.get_url());
+                    return;
+                };
+
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        log::debug!("connected to mqtt broker {}", plug.get_url());
+                        connected_sender.send_replace(true);
+                        // (re)subscribe, as the session is not persisted by the broker
+                        if task_client.subscribe(&plug.state_topic, QoS::AtLeastOnce).await.is_err() {
+                            log::warn!("failed to subscribe to mqtt topic {}", plug.state_topic);
+                        }
+                    },
+                    Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == plug.state_topic => {
+                        match plug.parse_state(&publish.payload) {
+                            Some(state) => {
+                                log::debug!("mqtt plug reported state {}", if state { "on" } else { "off" });
+                                *power.lock().unwrap() = Some(state);
+                            },
+                            None => log::warn!("could not parse mqtt plug state from payload on {}", plug.state_topic),
+                        }
+                    },
+                    Ok(_) => (),
+                    Err(error) => {
+                        log::warn!("lost connection to mqtt broker {}: {error}", plug.get_url());
+                        connected_sender.send_replace(false);
+                        tokio::time::sleep(RECONNECT_INTERVAL).await;
+                    },
+                }
+            }
+        });
+
+        Connection { client, power, connected }
+    }
+
+    /// clone without sharing the connection, to avoid keeping it alive
+    fn clone_config(&self) -> Self {
+        Self { connection: Arc::default(), ..self.clone() }
+    }
+
+    /// parse payload received on state topic
+    fn parse_state(&self, payload: &[u8]) -> Option<bool> {
+        let payload = std::str::from_utf8(payload).ok()?.trim();
+        let state = match self.state_pointer {
+            Some(ref pointer) => {
+                let json = serde_json::from_str::<serde_json::Value>(payload).ok()?;
+                match json.pointer(pointer)? {
+                    serde_json::Value::String(string) => string.clone(),
+                    value => value.to_string(),
+                }
+            },
+            None => payload.to_string(),
+        };
+
+        if state.eq_ignore_ascii_case(&self.state_on) {
+            Some(true)
+        } else if state.eq_ignore_ascii_case(&self.state_off) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl PowerSwitch for Plug {
+    async fn set_power(&self, power: bool) -> Result<(), Error> {
+        let payload = if power { &self.payload_on } else { &self.payload_off };
+        log::debug!("publishing {payload} to mqtt topic {}", self.command_topic);
+
+        self.connect().await?;
+        self.connection().await.client
+            .publish(&self.command_topic, QoS::AtLeastOnce, false, payload.clone()).await
+            .map_err(|_| Error::SendingRequest)
+    }
+
+    /// last state the plug reported, which is usually retained by the broker
+    async fn get_power(&self) -> Result<bool, Error> {
+        self.connect().await?;
+        let power = *self.connection().await.power.lock().unwrap();
+        power.ok_or(Error::NoState)
+    }
+
+    fn describe(&self) -> String {
+        format!("MQTT plug on topic {} of broker {}", self.command_topic, self.get_url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Plug, Config};
+
+    fn plug(state_pointer: Option<&str>) -> Plug {
+        Plug::new(Config {
+            broker_host: String::from("localhost"),
+            broker_port: 1883,
+            username: None,
+            password: None,
+            command_topic: String::from("zigbee2mqtt/plug/set"),
+            state_topic: String::from("zigbee2mqtt/plug"),
+            payload_on: String::from(r#"{"state":"ON"}"#),
+            payload_off: String::from(r#"{"state":"OFF"}"#),
+            state_pointer: state_pointer.map(String::from),
+            state_on: String::from("ON"),
+            state_off: String::from("OFF"),
+        })
+    }
+
+    #[test]
+    fn parse_plain_state() {
+        let plug = plug(None);
+        assert_eq!(plug.parse_state(b"ON"), Some(true));
+        assert_eq!(plug.parse_state(b"off\n"), Some(false));
+        assert_eq!(plug.parse_state(b"TOGGLE"), None);
+    }
+
+    #[test]
+    fn parse_json_state() {
+        let plug = plug(Some("/state"));
+        assert_eq!(plug.parse_state(br#"{"state":"ON","linkquality":120}"#), Some(true));
+        assert_eq!(plug.parse_state(br#"{"state":"OFF"}"#), Some(false));
+        assert_eq!(plug.parse_state(br#"{"linkquality":120}"#), None);
+        assert_eq!(plug.parse_state(b"ON"), None);
+    }
+}
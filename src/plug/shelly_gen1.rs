@@ -0,0 +1,64 @@
+//! for shelly smart plugs compatible with the following API <https://shelly-api-docs.shelly.cloud/gen1/#shelly-plug-plugs-relay-0>
+
+use reqwest::StatusCode;
+
+use super::{Error, PowerSwitch};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Plug {
+    base_url: String,
+}
+
+impl Plug {
+    pub const fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+
+    pub const fn get_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+}
+
+impl PowerSwitch for Plug {
+    async fn set_power(&self, power: bool) -> Result<(), Error> {
+        let turn = if power { "on" } else { "off" };
+        let base_url = &self.base_url;
+        let url = format!("{base_url}/relay/0?turn={turn}");
+        log::debug!("requesting url: {url}");
+
+        let response = reqwest::get(url).await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        match response.unwrap().status() {
+            StatusCode::OK => Ok(()),
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+
+    async fn get_power(&self) -> Result<bool, Error> {
+        let base_url = &self.base_url;
+        let url = format!("{base_url}/relay/0");
+        log::debug!("requesting url: {url}");
+
+        let response = reqwest::get(url).await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        let response = response.unwrap();
+        match response.status() {
+            StatusCode::OK => {
+                let json = response.json::<serde_json::Value>().await
+                    .map_err(|_| Error::UnexpectedResponse)?;
+                json["ison"].as_bool().ok_or(Error::UnexpectedResponse)
+            },
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("Shelly Gen1 plug at {}", self.base_url)
+    }
+}
@@ -0,0 +1,103 @@
+//! for smart plugs running tasmota, compatible with the following API <https://tasmota.github.io/docs/Commands/#with-web-requests>
+
+use reqwest::StatusCode;
+
+use super::{Error, PowerSwitch};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Plug {
+    base_url: String,
+}
+
+impl Plug {
+    pub const fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+
+    pub const fn get_url(&self) -> &str {
+        self.base_url.as_str()
+    }
+
+    /// send given command and return the power state from the response
+    async fn command(&self, command: &str) -> Result<bool, Error> {
+        let base_url = &self.base_url;
+        let url = format!("{base_url}/cm?cmnd={command}");
+        log::debug!("requesting url: {url}");
+
+        let response = reqwest::get(url).await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        let response = response.unwrap();
+        match response.status() {
+            StatusCode::OK => {
+                let json = response.json::<serde_json::Value>().await
+                    .map_err(|_| Error::UnexpectedResponse)?;
+                parse_power(&json)
+            },
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+}
+
+impl PowerSwitch for Plug {
+    async fn set_power(&self, power: bool) -> Result<(), Error> {
+        let command = if power { "Power%20On" } else { "Power%20Off" };
+        if self.command(command).await? == power {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedResponse)
+        }
+    }
+
+    async fn get_power(&self) -> Result<bool, Error> {
+        self.command("Power").await
+    }
+
+    fn describe(&self) -> String {
+        format!("Tasmota plug at {}", self.base_url)
+    }
+}
+
+/// parse responses like `{"POWER":"ON"}`.
+/// devices with multiple relays respond with `POWER1`, `POWER2`, ... instead, of which the first one is used.
+fn parse_power(json: &serde_json::Value) -> Result<bool, Error> {
+    let power = json.get("POWER")
+        .or_else(|| json.get("POWER1"))
+        .and_then(serde_json::Value::as_str)
+        .ok_or(Error::UnexpectedResponse)?;
+
+    match power.to_ascii_uppercase().as_str() {
+        "ON" => Ok(true),
+        "OFF" => Ok(false),
+        _ => Err(Error::UnexpectedResponse),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_power;
+    use serde_json::json;
+
+    #[test]
+    fn parse_on() {
+        assert!(parse_power(&json!({ "POWER": "ON" })).unwrap());
+    }
+
+    #[test]
+    fn parse_off() {
+        assert!(!parse_power(&json!({ "POWER": "OFF" })).unwrap());
+    }
+
+    #[test]
+    fn parse_multiple_relays() {
+        assert!(parse_power(&json!({ "POWER1": "ON", "POWER2": "OFF" })).unwrap());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_power(&json!({ "Command": "Unknown" })).is_err());
+        assert!(parse_power(&json!({ "POWER": "TOGGLE" })).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! for diy relays (e.g. esphome, custom firmware or home assistant scripts)
+//! controlled by user-configured http requests.
+//!
+//! urls and body are templates, in which `{power}` is replaced with `true`/`false`
+//! and `{turn}` is replaced with `on`/`off`.
+
+use reqwest::StatusCode;
+
+use super::{Error, PowerSwitch};
+
+/// http method to switch the plug with
+#[derive(Debug, Clone, Copy, Default, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    #[default]
+    Get,
+    Post,
+    Put,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Plug {
+    /// url template to request to turn the plug on
+    on_url: String,
+    /// url template to request to turn the plug off
+    off_url: String,
+    method: Method,
+    /// body template to send when switching the plug
+    body: Option<String>,
+    /// url to get the power state from with a get request
+    state_url: String,
+    /// JSON pointer to the state inside the response from `state_url`, e.g. `/state`
+    state_pointer: String,
+}
+
+/// configuration of a webhook plug, for [`Plug::new`]
+pub struct Config {
+    pub on_url: String,
+    pub off_url: String,
+    pub method: Method,
+    pub body: Option<String>,
+    pub state_url: String,
+    pub state_pointer: String,
+}
+
+impl Plug {
+    pub fn new(config: Config) -> Self {
+        Self {
+            on_url: config.on_url,
+            off_url: config.off_url,
+            method: config.method,
+            body: config.body,
+            state_url: config.state_url,
+            state_pointer: config.state_pointer,
+        }
+    }
+
+    pub const fn get_url(&self) -> &str {
+        self.state_url.as_str()
+    }
+}
+
+impl PowerSwitch for Plug {
+    async fn set_power(&self, power: bool) -> Result<(), Error> {
+        let url = render(if power { &self.on_url } else { &self.off_url }, power);
+        let method = match self.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+        };
+        log::debug!("requesting url with method {method}: {url}");
+
+        let mut request = reqwest::Client::new().request(method, url);
+        if let Some(ref body) = self.body {
+            let body = render(body, power);
+            let content_type = if serde_json::from_str::<serde_json::Value>(&body).is_ok() { "application/json" } else { "text/plain" };
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body);
+        }
+
+        let response = request.send().await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        match response.unwrap().status() {
+            code if code.is_success() => Ok(()),
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+
+    async fn get_power(&self) -> Result<bool, Error> {
+        let url = &self.state_url;
+        log::debug!("requesting url: {url}");
+
+        let response = reqwest::get(url).await;
+        if response.is_err() {
+            return Err(Error::SendingRequest);
+        }
+
+        let response = response.unwrap();
+        match response.status() {
+            StatusCode::OK => {
+                let json = response.json::<serde_json::Value>().await
+                    .map_err(|_| Error::UnexpectedResponse)?;
+                json.pointer(&self.state_pointer)
+                    .and_then(parse_power)
+                    .ok_or(Error::UnexpectedResponse)
+            },
+            code => Err(Error::UnexpectedStatusCode(code))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("webhook plug with state at {}", self.state_url)
+    }
+}
+
+/// replace placeholders in template
+fn render(template: &str, power: bool) -> String {
+    template
+        .replace("{power}", if power { "true" } else { "false" })
+        .replace("{turn}", if power { "on" } else { "off" })
+}
+
+/// interpret a json value as power state, e.g. `true`, `"ON"` or `1`
+fn parse_power(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(power) => Some(*power),
+        serde_json::Value::Number(number) => number.as_f64().map(|number| number != 0.),
+        serde_json::Value::String(string) => match string.to_ascii_lowercase().as_str() {
+            "on" | "true" | "1" => Some(true),
+            "off" | "false" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, parse_power};
+    use serde_json::json;
+
+    #[test]
+    fn render_template() {
+        assert_eq!(render("http://relay/switch/light/turn_{turn}", true), "http://relay/switch/light/turn_on");
+        assert_eq!(render(r#"{"on":{power}}"#, false), r#"{"on":false}"#);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_power(&json!(true)), Some(true));
+        assert_eq!(parse_power(&json!("OFF")), Some(false));
+        assert_eq!(parse_power(&json!(1)), Some(true));
+        assert_eq!(parse_power(&json!("unavailable")), None);
+        assert_eq!(parse_power(&json!(null)), None);
+    }
+}
@@ -0,0 +1,26 @@
+//! enforce the expected power state of plugs instead of only switching them at timer edges,
+//! e.g. after starting mid-day, after a command failed or after a plug was switched by hand
+
+use crate::time::{self, Time};
+use crate::state::Enclosures;
+use crate::actuator::{Actuator, PlugId};
+
+/// compare actual and expected power state of all plugs in enclosures with reconciliation enabled
+/// and correct any drift in the background. the actuator skips plugs with a pending command
+pub fn reconcile(enclosures: &Enclosures, actuator: &Actuator) {
+    for (id, state) in enclosures.iter().filter(|(_, state)| state.reconcile) {
+        let now = Time::now(state.timezone);
+        let today = Time::today(state.timezone);
+
+        for (name, device) in &state.plugs {
+            let plug_id = PlugId { enclosure: id.clone(), name: name.clone() };
+            let expected = device.overridden_power(time::now()).or_else(|| state.automation.schedule_of(device)
+                .map(|schedule| schedule.is_on_at(&state.year_timer, today, now)));
+            let Some(expected) = expected else {
+                log::trace!("skipping reconciliation of plug {plug_id}, as automation is paused");
+                continue;
+            };
+            actuator.reconcile(plug_id, device.plug.clone(), device.retry_policy, expected);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+/// how to retry failed commands to a plug
+#[derive(Debug, Clone, Copy, PartialEq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+pub struct Policy {
+    /// Maximum number of retries after the first attempt
+    #[schema(example = 35)]
+    pub max_retries: u16,
+    /// How the interval between retries increases
+    pub backoff: Backoff,
+    /// Seconds to wait before the first retry
+    #[schema(example = 6)]
+    pub initial_interval_seconds: u32,
+    /// Maximum seconds to wait between retries
+    #[schema(example = 60)]
+    pub max_interval_seconds: u32,
+    /// Seconds after the first attempt to give up retrying, regardless of `max_retries`
+    #[schema(example = 1800)]
+    pub deadline_seconds: Option<u32>,
+    /// Fraction of every interval to randomly add or subtract, between 0.0 and 1.0
+    #[schema(minimum = 0.0, maximum = 1.0, example = 0.1)]
+    pub jitter: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backoff {
+    /// Always wait `initial_interval_seconds`
+    Constant,
+    /// Wait `initial_interval_seconds` times the number of the retry
+    Linear,
+    /// Double the interval after every retry
+    Exponential,
+}
+
+/// retry on error for about 30min, with increasing interval between requests (up to 1min).
+/// about 5min for 10 linear increase interval retries + about 25min for 25 1min interval retries.
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            max_retries: 35,
+            backoff: Backoff::Linear,
+            initial_interval_seconds: 6,
+            max_interval_seconds: 60,
+            deadline_seconds: None,
+            jitter: 0.,
+        }
+    }
+}
+
+impl Policy {
+    /// interval to wait before given retry (starting at 1), without jitter
+    pub fn interval(&self, retry: u16) -> Duration {
+        let initial = u64::from(self.initial_interval_seconds);
+        let seconds = match self.backoff {
+            Backoff::Constant => initial,
+            Backoff::Linear => initial.saturating_mul(u64::from(retry)),
+            Backoff::Exponential => initial.saturating_mul(1_u64.checked_shl(u32::from(retry).saturating_sub(1)).unwrap_or(u64::MAX)),
+        };
+        Duration::from_secs(seconds.min(u64::from(self.max_interval_seconds)))
+    }
+
+    /// interval to wait before given retry (starting at 1), with random jitter applied
+    pub fn interval_with_jitter(&self, retry: u16) -> Duration {
+        let interval = self.interval(retry);
+        if self.jitter <= 0. {
+            return interval;
+        }
+
+        // random number between -1.0 and 1.0
+        let random = fastrand::f64().mul_add(2., -1.);
+        interval.mul_f64(f64::from(self.jitter).mul_add(random, 1.).max(0.))
+    }
+
+    /// whether waiting another `interval` after `elapsed` would exceed the deadline
+    pub fn exceeds_deadline(&self, elapsed: Duration, interval: Duration) -> bool {
+        self.deadline_seconds
+            .is_some_and(|deadline| elapsed + interval > Duration::from_secs(u64::from(deadline)))
+    }
+}
+
+/// status of a command that failed at least once and is being retried
+#[derive(Debug, Clone, Copy, utoipa::ToSchema, serde::Serialize)]
+pub struct Status {
+    /// Power state the plug is supposed to be switched to
+    pub power: bool,
+    /// Number of retries attempted so far
+    pub retries: u16,
+    /// When the first attempt was made
+    #[schema(value_type = String, example = "2025-06-21T08:00:00Z")]
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the next retry will be attempted
+    #[schema(value_type = String, example = "2025-06-21T08:01:00Z")]
+    pub next_retry_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(backoff: Backoff) -> Policy {
+        Policy { backoff, ..Policy::default() }
+    }
+
+    #[test]
+    fn constant() {
+        let policy = policy(Backoff::Constant);
+        assert_eq!(policy.interval(1), Duration::from_secs(6));
+        assert_eq!(policy.interval(20), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn linear() {
+        let policy = policy(Backoff::Linear);
+        assert_eq!(policy.interval(1), Duration::from_secs(6));
+        assert_eq!(policy.interval(3), Duration::from_secs(18));
+        assert_eq!(policy.interval(20), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn exponential() {
+        let policy = policy(Backoff::Exponential);
+        assert_eq!(policy.interval(1), Duration::from_secs(6));
+        assert_eq!(policy.interval(3), Duration::from_secs(24));
+        assert_eq!(policy.interval(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn jitter() {
+        let policy = Policy { jitter: 0.5, ..Policy::default() };
+        for _ in 0 .. 100 {
+            let interval = policy.interval_with_jitter(1);
+            assert!(interval >= Duration::from_secs(3));
+            assert!(interval <= Duration::from_secs(9));
+        }
+    }
+
+    #[test]
+    fn deadline() {
+        let policy = Policy { deadline_seconds: Some(60), ..Policy::default() };
+        assert!(!policy.exceeds_deadline(Duration::from_secs(30), Duration::from_secs(30)));
+        assert!(policy.exceeds_deadline(Duration::from_secs(30), Duration::from_secs(31)));
+        assert!(!Policy::default().exceeds_deadline(Duration::MAX / 2, Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,259 @@
+//! switches plugs at the transitions of their timers. instead of polling for matching times,
+//! it sleeps until the next transition and replays transitions it missed by waking late,
+//! e.g. after a suspend, a clock jump or long contention of the state lock.
+
+use std::sync::Arc;
+use chrono_tz::Tz;
+use tokio::sync::Notify;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+use crate::time::{self, Time};
+use crate::reconcile;
+use crate::device::Schedule;
+use crate::timer::{day, year};
+use crate::state::{State, StateWrapper, Automation};
+use crate::actuator::{Actuator, PlugId};
+use crate::constants::{MAX_SLEEP_INTERVAL, RECONCILE_INTERVAL};
+
+/// transitions older than this when detected were missed and get replayed
+const MISSED_TOLERANCE: TimeDelta = TimeDelta::minutes(1);
+
+/// how far to look ahead for the next transition. beyond this, the scheduler wakes up regularly anyway.
+const LOOKAHEAD: TimeDelta = TimeDelta::days(2);
+
+/// switch of a plug to a power state at an absolute moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transition {
+    at: DateTime<Utc>,
+    power: bool,
+}
+
+/// handle to wake the scheduler
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    changed: Arc<Notify>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// recompute the next transition, e.g. after the configuration changed
+    pub fn reschedule(&self) {
+        self.changed.notify_one();
+    }
+
+    /// switch plugs of all enclosures at their transitions. never terminates.
+    pub async fn run(self, state: StateWrapper, actuator: Actuator) {
+        // transitions up to this moment were already handled
+        let mut last_checked = time::now();
+        // to reconcile on startup and periodically afterwards
+        let mut last_reconciled: Option<tokio::time::Instant> = None;
+
+        loop {
+            let mut enclosures = state.lock().await;
+            let now = time::now();
+            if enclosures.is_empty() {
+                log::trace!("nothing to schedule, no enclosures configured");
+            }
+
+            if now < last_checked {
+                log::warn!("clock jumped back from {last_checked} to {now}, not replaying any transitions");
+                last_checked = now;
+            }
+
+            let mut next_at: Option<DateTime<Utc>> = None;
+            // whether expired overrides or pauses were removed
+            let mut state_changed = false;
+            for (id, state) in enclosures.iter_mut() {
+                let timezone = state.timezone;
+                if cfg!(feature = "demo_mode") {
+                    log::debug!("it is {} in enclosure {id}", Time::now(timezone));
+                }
+
+                let resumes_at = state.automation.resumes_at(timezone);
+                let resumed = resumes_at.is_some_and(|resumes_at| resumes_at <= now);
+                if resumed {
+                    log::info!("resuming automation of enclosure {id}");
+                    state.automation = Automation::Active;
+                    state_changed = true;
+                } else if let Some(resumes_at) = resumes_at {
+                    next_at = Some(next_at.map_or(resumes_at, |next_at| next_at.min(resumes_at)));
+                }
+
+                let year_timer = &state.year_timer;
+                for (name, device) in &mut state.plugs {
+                    let plug_id = PlugId { enclosure: id.clone(), name: name.clone() };
+                    // no transitions at all while paused
+                    let schedule = state.automation.schedule_of(device);
+                    let day_timer = |date| schedule.as_ref()
+                        .map_or_else(day::Timer::off, |schedule| schedule.day_timer(year_timer, date));
+                    let overridden_until = device.power_override.map(|power_override| power_override.until);
+                    let override_expired = overridden_until.is_some_and(|until| until <= now);
+
+                    // transitions while the plug was overridden are superseded by the override,
+                    // and only the latest one matters if multiple were missed
+                    let from = overridden_until.map_or(last_checked, |until| until.max(last_checked));
+                    let transitions = transitions_between(day_timer, timezone, from, now);
+                    if let Some(transition) = transitions.last() {
+                        let turn = if transition.power { "on" } else { "off" };
+                        let local = transition.at.with_timezone(&timezone);
+                        if now - transition.at > MISSED_TOLERANCE {
+                            log::warn!("missed {} transition(s) of plug {plug_id}, replaying the latest one from {local} by turning it {turn}",
+                                transitions.len());
+                        } else {
+                            log::info!("reached transition at {local}, turning plug {plug_id} {turn}");
+                        }
+                        actuator.switch(plug_id.clone(), device.plug.clone(), device.retry_policy, transition.power);
+                    } else if (override_expired || resumed) && device.overridden_power(now).is_none()
+                        && let Some(schedule) = &schedule {
+                        let power = schedule.is_on_at(year_timer, Time::today(timezone), Time::now(timezone));
+                        log::info!("resuming schedule of plug {plug_id} by turning it {}", if power { "on" } else { "off" });
+                        actuator.switch(plug_id.clone(), device.plug.clone(), device.retry_policy, power);
+                    }
+
+                    let next = match overridden_until {
+                        Some(until) if !override_expired => Some(until),
+                        _ => next_transition(day_timer, timezone, now).map(|transition| transition.at),
+                    };
+                    if let Some(next) = next {
+                        next_at = Some(next_at.map_or(next, |next_at| next_at.min(next)));
+                    }
+
+                    if override_expired {
+                        log::debug!("removing expired override of plug {plug_id}");
+                        device.power_override = None;
+                        state_changed = true;
+                    }
+                }
+            }
+            last_checked = now;
+
+            if last_reconciled.is_none_or(|last_reconciled| last_reconciled.elapsed() >= RECONCILE_INTERVAL) {
+                log::debug!("reconciling power state of plugs");
+                reconcile::reconcile(&enclosures, &actuator);
+                last_reconciled = Some(tokio::time::Instant::now());
+            }
+            drop(enclosures);
+            if state_changed {
+                State::write_to_file(Arc::clone(&state));
+            }
+
+            // wake up regularly anyway to notice clock jumps and to reconcile
+            let until_reconcile = RECONCILE_INTERVAL.saturating_sub(last_reconciled.map(|last| last.elapsed()).unwrap_or_default());
+            let duration = next_at.map_or(MAX_SLEEP_INTERVAL, time::real_duration_until)
+                .min(MAX_SLEEP_INTERVAL)
+                .min(until_reconcile);
+            log::trace!("sleeping for {duration:?}, next transition at {next_at:?}");
+
+            tokio::select! {
+                () = tokio::time::sleep(duration) => {},
+                () = self.changed.notified() => log::debug!("woken up to reschedule"),
+            }
+        }
+    }
+}
+
+/// moment of the first transition of the schedule after `after` within the lookahead, regardless of overrides
+pub fn next_transition_of(schedule: &Schedule, year_timer: &year::Timer, timezone: Tz, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    next_transition(|date| schedule.day_timer(year_timer, date), timezone, after).map(|transition| transition.at)
+}
+
+/// transitions of all intervals of the timer of given local date, the last one possibly ending on the following date
+fn transitions_on(day_timer: &day::Timer, date: NaiveDate, timezone: Tz) -> Vec<Transition> {
+    day_timer.intervals().iter().flat_map(|interval| [
+        Transition { at: interval.on_time().on(date, timezone).moment(), power: true },
+        Transition { at: interval.off_time().on(interval.off_date(date), timezone).moment(), power: false },
+    ]).collect()
+}
+
+/// transitions after `from` up to and including `to` in chronological order,
+/// given the timer to follow on every date
+fn transitions_between(
+    day_timer: impl Fn(NaiveDate) -> day::Timer,
+    timezone: Tz,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<Transition> {
+    // local dates might differ from utc ones, so start a day early to be safe
+    let first = from.with_timezone(&timezone).date_naive().pred_opt().unwrap();
+    let last = to.with_timezone(&timezone).date_naive();
+
+    let mut transitions = first.iter_days()
+        .take_while(|date| *date <= last)
+        .flat_map(|date| transitions_on(&day_timer(date), date, timezone))
+        .filter(|transition| from < transition.at && transition.at <= to)
+        .collect::<Vec<_>>();
+    transitions.sort_by_key(|transition| transition.at);
+    transitions
+}
+
+/// first transition after `after` within the lookahead
+fn next_transition(
+    day_timer: impl Fn(NaiveDate) -> day::Timer,
+    timezone: Tz,
+    after: DateTime<Utc>,
+) -> Option<Transition> {
+    transitions_between(day_timer, timezone, after, after + LOOKAHEAD).into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const TIMEZONE: Tz = chrono_tz::Europe::Berlin;
+
+    fn day_timer(_: NaiveDate) -> day::Timer {
+        day::Timer::new(Time::new(8, 0), Time::new(20, 0))
+    }
+
+    fn moment(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        TIMEZONE.with_ymd_and_hms(2025, 6, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn between() {
+        let transitions = transitions_between(day_timer, TIMEZONE, moment(21, 7, 59), moment(21, 8, 0));
+        assert_eq!(transitions, [Transition { at: moment(21, 8, 0), power: true }]);
+    }
+
+    #[test]
+    fn between_excludes_start() {
+        assert!(transitions_between(day_timer, TIMEZONE, moment(21, 8, 0), moment(21, 19, 59)).is_empty());
+    }
+
+    #[test]
+    fn missed_days() {
+        let transitions = transitions_between(day_timer, TIMEZONE, moment(21, 9, 0), moment(23, 7, 0));
+        assert_eq!(transitions.len(), 3);
+        assert_eq!(transitions.last(), Some(&Transition { at: moment(22, 20, 0), power: false }));
+    }
+
+    #[test]
+    fn next() {
+        assert_eq!(next_transition(day_timer, TIMEZONE, moment(21, 12, 0)), Some(Transition { at: moment(21, 20, 0), power: false }));
+        assert_eq!(next_transition(day_timer, TIMEZONE, moment(21, 20, 0)), Some(Transition { at: moment(22, 8, 0), power: true }));
+    }
+
+    #[test]
+    fn over_midnight() {
+        let day_timer = |_| day::Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let transitions = transitions_between(day_timer, TIMEZONE, moment(21, 12, 0), moment(22, 12, 0));
+        assert_eq!(transitions, [
+            Transition { at: moment(21, 20, 0), power: true },
+            Transition { at: moment(22, 4, 0), power: false },
+        ]);
+    }
+
+    #[test]
+    fn dst_fires_once() {
+        let day_timer = |_| day::Timer::new(Time::new(2, 30), Time::new(20, 0));
+        for (from, to) in [((3, 29), (3, 31)), ((10, 25), (10, 27))] {
+            let from = TIMEZONE.with_ymd_and_hms(2025, from.0, from.1, 12, 0, 0).unwrap().to_utc();
+            let to = TIMEZONE.with_ymd_and_hms(2025, to.0, to.1, 0, 0, 0).unwrap().to_utc();
+            assert_eq!(transitions_between(day_timer, TIMEZONE, from, to).len(), 3);
+        }
+    }
+}
@@ -1,31 +1,133 @@
 use tokio::sync::Mutex;
 use std::sync::Arc;
+use std::collections::BTreeMap;
 use chrono_tz::Tz;
+use chrono::{DateTime, NaiveDate, Utc};
 
 use crate::time::Time;
-use crate::plug::Plug;
-use crate::timer::year;
+use crate::device::{Device, Schedule};
+use crate::timer::{day, year, adjustment::Adjustment};
 
 #[allow(clippy::module_name_repetitions)]
-pub type StateWrapper = Arc<Mutex<Option<State>>>;
+pub type StateWrapper = Arc<Mutex<Enclosures>>;
 
-/// state of the application (also saved/loaded from/to json file)
+/// state of the application (also saved/loaded from/to json file):
+/// configured enclosures (terrariums) by their unique id
+pub type Enclosures = BTreeMap<String, State>;
+
+/// id of enclosure to migrate state files from before multiple enclosures were supported to
+const MIGRATED_ENCLOSURE_ID: &str = "default";
+
+/// name of plug to migrate state files from before multiple plugs were supported to
+const MIGRATED_PLUG_NAME: &str = "main";
+
+/// how the computed timers of an enclosure are determined
+#[derive(Debug, Clone, Default, PartialEq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mode {
+    /// From sunrise/sunset times at the local and natural coordinates
+    #[default]
+    Astronomical,
+    /// Same timer every day, without any sunrise data
+    Daily {
+        timer: day::Timer,
+    },
+    /// Timers for the 15th of every month from January to December,
+    /// linearly interpolated in between, without any sunrise data
+    Monthly {
+        timers: Box<[day::Timer; 12]>,
+    },
+}
+
+/// whether plugs of an enclosure are switched automatically
+#[derive(Debug, Clone, Default, PartialEq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Automation {
+    /// Plugs follow their schedules
+    #[default]
+    Active,
+    /// Plugs are not switched automatically, e.g. for maintenance
+    Paused {
+        /// Local date to resume automation on, indefinitely if missing
+        #[schema(value_type = Option<String>, example = "2025-08-01")]
+        until: Option<NaiveDate>,
+    },
+    /// All plugs follow the same safe timer every day instead of their schedules, e.g. while away
+    Vacation {
+        timer: day::Timer,
+        /// Local date to resume automation on, indefinitely if missing
+        #[schema(value_type = Option<String>, example = "2025-08-01")]
+        until: Option<NaiveDate>,
+    },
+}
+
+impl Automation {
+    /// moment to resume automation at in given timezone, if only suspended or replaced temporarily
+    pub fn resumes_at(&self, timezone: Tz) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Active => None,
+            Self::Paused { until } | Self::Vacation { until, .. } =>
+                until.map(|until| Time::new(0, 0).on(until, timezone).moment()),
+        }
+    }
+
+    /// schedule the device is switched by automatically: its own one, the vacation timer instead
+    /// or none while paused
+    pub fn schedule_of(&self, device: &Device) -> Option<Schedule> {
+        match self {
+            Self::Active => Some(device.schedule.clone()),
+            Self::Paused { .. } => None,
+            Self::Vacation { timer, .. } => Some(Schedule::Fixed { timer: timer.clone() }),
+        }
+    }
+}
+
+/// state of one enclosure
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Self")]
 pub struct State {
-    /// average sunrise/sunset times between local ones (0.0) and ones from the natural habitat (1.0)
-    pub natural_factor: f32,
+    /// how `year_timer` is computed. the following settings are only used if astronomical,
+    /// the factor and coordinates are only present then.
+    #[serde(default)]
+    pub mode: Mode,
+    /// average sunrise/sunset times between local ones (0.0) and ones from the natural habitat (1.0).
+    /// the average of `natural_factors` if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub natural_factor: Option<f32>,
+    /// natural factors for the 15th of every month, interpolated in between, instead of a single one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub natural_factors: Option<[f32; 12]>,
+    /// how local days and days of the natural habitat are combined
+    #[serde(default)]
+    pub blending: year::Blending,
+    /// which times of a day define turning on and off
+    #[serde(default)]
+    pub twilight: year::Twilight,
     /// latitude of geographic coordinates of terrarium, from -90° (south) to 90° (north)
-    pub local_latitude: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_latitude: Option<f32>,
     /// longitude of geographic coordinates of terrarium, from -180° (west) to 180° (east)
-    pub local_longitude: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_longitude: Option<f32>,
     /// latitude of geographic coordinates of the animals natural habitat, from -90° (south) to 90° (north)
-    pub natural_latitude: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub natural_latitude: Option<f32>,
     /// longitude of geographic coordinates of the animals natural habitat, from -180° (west) to 180° (east)
-    pub natural_longitude: f32,
-    /// plug to control
-    pub plug: Plug,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub natural_longitude: Option<f32>,
+    /// applied to all three year timers after averaging
+    #[serde(default)]
+    pub adjustment: Adjustment,
+    /// plugs to control by their unique name
+    pub plugs: BTreeMap<String, Device>,
     /// timezone to use for timer activations
     pub timezone: Tz,
+    /// whether to periodically enforce the expected power state of plugs, not only at timer edges
+    #[serde(default)]
+    pub reconcile: bool,
+    /// whether plugs are switched automatically, independent of the computed timers
+    #[serde(default)]
+    pub automation: Automation,
     /// actual timers to turn plug on/off every day
     pub year_timer: year::Timer,
     /// same as `year_timer` if `natural_factor` is 0.0
@@ -34,12 +136,56 @@ pub struct State {
     pub natural_year_timer: year::Timer,
 }
 
+impl serde::Serialize for State {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for State {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        // states from before multiple plugs were supported have a single plug following the computed timers
+        if let serde_json::Value::Object(fields) = &mut value
+            && !fields.contains_key("plugs")
+            && let Some(plug) = fields.remove("plug") {
+            let device = serde_json::json!({ "plug": plug, "schedule": Schedule::Computed });
+            fields.insert(String::from("plugs"), serde_json::json!({ MIGRATED_PLUG_NAME: device }));
+        }
+        Self::deserialize(value).map_err(serde::de::Error::custom)
+    }
+}
+
 impl State {
-    pub fn read_from_file() -> Option<Self> {
+    /// enclosure with given timers and plugs, without any astronomical settings,
+    /// reconciliation or suspended automation
+    pub fn new(mode: Mode, plugs: BTreeMap<String, Device>, timezone: Tz, year_timer: year::Timer, local_year_timer: year::Timer, natural_year_timer: year::Timer) -> Self {
+        Self {
+            mode,
+            natural_factor: None,
+            natural_factors: None,
+            blending: year::Blending::default(),
+            twilight: year::Twilight::default(),
+            local_latitude: None,
+            local_longitude: None,
+            natural_latitude: None,
+            natural_longitude: None,
+            adjustment: Adjustment::default(),
+            plugs,
+            timezone,
+            reconcile: false,
+            automation: Automation::default(),
+            year_timer,
+            local_year_timer,
+            natural_year_timer,
+        }
+    }
+
+    pub fn read_from_file() -> Enclosures {
         let path = dirs_next::data_dir();
         if path.is_none() {
             log::debug!("couldn't get path to data directory, operating system probably unsupported");
-            return None;
+            return Enclosures::new();
         }
 
         let mut path = path.unwrap();
@@ -48,22 +194,39 @@ impl State {
         let content = std::fs::read_to_string(path);
         if content.is_err() {
             log::info!("no state file found, waiting for configuration");
-            return None;
+            return Enclosures::new();
         }
 
-        let state = serde_json::from_str::<Self>(&content.unwrap());
-        if state.is_err() {
+        let enclosures = Self::parse_file(&content.unwrap());
+        if enclosures.is_err() {
             log::warn!("read state file, but content did not have the expected structure");
-            return None;
+            return Enclosures::new();
         }
 
         log::info!("successfully read last state from file");
 
-        let state = state.unwrap();
-        let timezone = state.timezone;
-        log::info!("using timezone {timezone}, current time is {}", Time::now(timezone));
+        let enclosures = enclosures.unwrap();
+        for (id, state) in &enclosures {
+            let timezone = state.timezone;
+            log::info!("enclosure {id} uses timezone {timezone}, current time is {}", Time::now(timezone));
+        }
+
+        enclosures
+    }
 
-        Some(state)
+    /// enclosures from content of a state file, migrating ones from before multiple enclosures were supported.
+    /// plugs of those are migrated by deserializing `State`.
+    fn parse_file(content: &str) -> serde_json::Result<Enclosures> {
+        serde_json::from_str::<Enclosures>(content).or_else(|error| {
+            // state file might be from before multiple enclosures were supported
+            match serde_json::from_str::<Option<Self>>(content) {
+                Ok(state) => {
+                    log::info!("migrating state file to multiple enclosures, using id \"{MIGRATED_ENCLOSURE_ID}\"");
+                    Ok(state.map(|state| Enclosures::from([(MIGRATED_ENCLOSURE_ID.to_string(), state)])).unwrap_or_default())
+                },
+                Err(_) => Err(error),
+            }
+        })
     }
 
     pub fn write_to_file(state: StateWrapper) {
@@ -87,3 +250,43 @@ impl State {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// state file as written before multiple plugs and plug backends were supported
+    fn baseline_state_file() -> String {
+        let day_timers = vec![r#"{"on_time":{"hour":8,"minute":0},"off_time":{"hour":18,"minute":0}}"#; 366].join(",");
+        format!(r#"{{"natural_factor":0.5,"local_latitude":52.5,"local_longitude":13.4,"natural_latitude":-12.0,"natural_longitude":131.0,"plug":{{"base_url":"http://192.168.0.2"}},"timezone":"Europe/Berlin","year_timer":{{"day_timers":[{day_timers}]}},"local_year_timer":{{"day_timers":[{day_timers}]}},"natural_year_timer":{{"day_timers":[{day_timers}]}}}}"#)
+    }
+
+    #[test]
+    fn deserialize_single_plug() {
+        let state = serde_json::from_str::<State>(&baseline_state_file()).unwrap();
+        assert_eq!(state.plugs.len(), 1);
+        let device = &state.plugs[MIGRATED_PLUG_NAME];
+        assert_eq!(device.plug.get_url(), "http://192.168.0.2");
+        assert_eq!(device.schedule, Schedule::Computed);
+        assert_eq!(state.timezone, chrono_tz::Europe::Berlin);
+    }
+
+    #[test]
+    fn parse_baseline_file() {
+        let enclosures = State::parse_file(&baseline_state_file()).unwrap();
+        assert_eq!(enclosures.len(), 1);
+        let state = &enclosures[MIGRATED_ENCLOSURE_ID];
+        assert_eq!(state.plugs[MIGRATED_PLUG_NAME].plug.get_url(), "http://192.168.0.2");
+        assert_eq!(state.local_latitude, Some(52.5));
+
+        // written again in the current format
+        let content = serde_json::to_string(&enclosures).unwrap();
+        let enclosures = State::parse_file(&content).unwrap();
+        assert!(enclosures[MIGRATED_ENCLOSURE_ID].plugs.contains_key(MIGRATED_PLUG_NAME));
+    }
+
+    #[test]
+    fn parse_unconfigured_baseline_file() {
+        assert!(State::parse_file("null").unwrap().is_empty());
+    }
+}
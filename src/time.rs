@@ -1,4 +1,57 @@
 use chrono_tz::Tz;
+use chrono::{DateTime, NaiveDate, Offset, TimeDelta, TimeZone, Utc};
+
+/// current moment. in demo mode, time flows accelerated since the start of the application.
+pub fn now() -> DateTime<Utc> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "demo_mode")] {
+            let start = *demo::START;
+            start + ((Utc::now() - start) * demo::ACCELERATION)
+        } else {
+            Utc::now()
+        }
+    }
+}
+
+/// real duration until given moment, which is zero if it already passed
+pub fn real_duration_until(moment: DateTime<Utc>) -> std::time::Duration {
+    let duration = moment - now();
+    #[cfg(feature = "demo_mode")]
+    let duration = duration / demo::ACCELERATION;
+    duration.to_std().unwrap_or_default()
+}
+
+#[cfg(feature = "demo_mode")]
+mod demo {
+    use std::sync::LazyLock;
+    use chrono::{DateTime, Utc};
+    use crate::constants::MILLISECONDS_PER_MINUTE;
+
+    /// real start of the application, from which on time flows accelerated
+    pub static START: LazyLock<DateTime<Utc>> = LazyLock::new(Utc::now);
+
+    /// simulated time per real time
+    pub const ACCELERATION: i32 = 60 * 1000 / MILLISECONDS_PER_MINUTE as i32;
+}
+
+/// moment that a local time on some date corresponds to, considering DST changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Localized {
+    /// local time exists exactly once
+    Unique(DateTime<Utc>),
+    /// local time is skipped by a DST change, so it is shifted forward by the length of the gap
+    Skipped(DateTime<Utc>),
+    /// local time is repeated by a DST change, so only its earliest occurrence is used
+    Ambiguous(DateTime<Utc>),
+}
+
+impl Localized {
+    pub const fn moment(self) -> DateTime<Utc> {
+        match self {
+            Self::Unique(moment) | Self::Skipped(moment) | Self::Ambiguous(moment) => moment,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
 pub struct Time {
@@ -42,23 +95,37 @@ impl Time {
         Ok(Self::new(hour, minute))
     }
 
-    #[allow(clippy::items_after_statements)]
+    /// current time of day in given timezone
     pub fn now(timezone: Tz) -> Self {
-        let now = chrono::Utc::now().with_timezone(&timezone);
-
-        cfg_if::cfg_if! {
-            if #[cfg(feature = "demo_mode")] {
-                // accelerate flow of time
-                use crate::constants::MILLISECONDS_PER_MINUTE;
-                let minutes = (now.timestamp_millis() / i64::from(MILLISECONDS_PER_MINUTE)) % (24 * 60);
-                Self::from_minutes(minutes.try_into().unwrap())
-            } else {
-                use chrono::Timelike;
-                Self::new(
-                    now.hour().try_into().unwrap(),
-                    now.minute().try_into().unwrap(),
-                )
-            }
+        use chrono::Timelike;
+        let now = now().with_timezone(&timezone);
+        Self::new(
+            now.hour().try_into().unwrap(),
+            now.minute().try_into().unwrap(),
+        )
+    }
+
+    /// current date in given timezone
+    pub fn today(timezone: Tz) -> NaiveDate {
+        now().with_timezone(&timezone).date_naive()
+    }
+
+    /// moment of this (valid) time on given date in given timezone
+    pub fn on(self, date: NaiveDate, timezone: Tz) -> Localized {
+        let local = date.and_hms_opt(
+            self.hour.try_into().unwrap(),
+            self.minute.try_into().unwrap(),
+            0,
+        ).unwrap();
+
+        match timezone.from_local_datetime(&local) {
+            chrono::LocalResult::Single(moment) => Localized::Unique(moment.to_utc()),
+            chrono::LocalResult::Ambiguous(earliest, _) => Localized::Ambiguous(earliest.to_utc()),
+            chrono::LocalResult::None => {
+                // use offset from before the gap, which moves the time forward by its length
+                let offset = timezone.offset_from_utc_datetime(&(local - TimeDelta::days(1))).fix();
+                Localized::Skipped(local.and_utc() - TimeDelta::seconds(offset.local_minus_utc().into()))
+            },
         }
     }
 
@@ -67,12 +134,14 @@ impl Time {
             log::debug!("using timezone from sunrise API");
             return timezone;
         }
+        Self::local_zone()
+    }
 
-        if let Ok(timezone) = iana_time_zone::get_timezone() {
-            if let Ok(timezone) = timezone.parse::<Tz>() {
-                log::debug!("using local timezone from iana-time-zone");
-                return timezone;
-            }
+    /// timezone of the system, or a default one if it can't be determined
+    pub fn local_zone() -> Tz {
+        if let Some(timezone) = iana_time_zone::get_timezone().ok().and_then(|timezone| timezone.parse::<Tz>().ok()) {
+            log::debug!("using local timezone from iana-time-zone");
+            return timezone;
         }
 
         log::warn!("could not determine timezone, using default");
@@ -84,15 +153,25 @@ impl Time {
         (0 ..= 23).contains(&self.hour) && (0 ..= 59).contains(&self.minute)
     }
 
+    pub const fn hour(self) -> i8 {
+        self.hour
+    }
+
     pub const fn minute(self) -> i8 {
         self.minute
     }
 
-    fn minutes(self) -> i16 {
+    /// total minutes since midnight
+    pub fn minutes(self) -> i16 {
         i16::from(self.minute) + (i16::from(self.hour) * 60)
     }
 
-    fn from_minutes(minutes: i16) -> Self {
+    /// normal day time by wrapping around midnight, e.g. 25:00 becomes 01:00 and -01:00 becomes 23:00
+    pub fn wrapped(self) -> Self {
+        Self::from_minutes(self.minutes().rem_euclid(24 * 60))
+    }
+
+    pub fn from_minutes(minutes: i16) -> Self {
         let hour = minutes / 60;
         let minute = minutes - (hour * 60);
 
@@ -165,7 +244,8 @@ impl std::ops::Mul<f32> for Time {
 
 #[cfg(test)]
 mod tests {
-    use super::Time;
+    use super::{Time, Localized};
+    use chrono::{NaiveDate, TimeZone, Utc};
     const ZERO: Time = Time::new(0, 0);
 
     #[test]
@@ -231,6 +311,14 @@ mod tests {
         assert_eq!(Time::from_hhmmss("18:42:59").unwrap(), Time::new(18, 42));
     }
 
+    #[test]
+    fn wrapped() {
+        assert_eq!((Time::new(20, 0) + Time::new(6, 30)).wrapped(), Time::new(2, 30));
+        assert_eq!((Time::new(4, 0) - Time::new(20, 0)).wrapped(), Time::new(8, 0));
+        assert_eq!(Time::new(24, 0).wrapped(), ZERO);
+        assert_eq!(Time::new(12, 0).wrapped(), Time::new(12, 0));
+    }
+
     fn negative_test(minutes: i16, hour: i8, minute: i8) {
         let time = Time::from_minutes(minutes);
         assert_eq!(time, Time::new(hour, minute));
@@ -240,4 +328,25 @@ mod tests {
     #[test] fn negative_1() { negative_test(-1, 0, -1); }
     #[test] fn negative_2() { negative_test(-60, -1, 0); }
     #[test] fn negative_3() { negative_test(-61, -1, -1); }
+
+    fn on(month: u32, day: u32) -> Localized {
+        Time::new(2, 30).on(NaiveDate::from_ymd_opt(2025, month, day).unwrap(), chrono_tz::Europe::Berlin)
+    }
+
+    #[test]
+    fn on_unique() {
+        assert_eq!(on(6, 21), Localized::Unique(Utc.with_ymd_and_hms(2025, 6, 21, 0, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn on_skipped() {
+        // 02:30 CET does not exist, shifted to 03:30 CEST
+        assert_eq!(on(3, 30), Localized::Skipped(Utc.with_ymd_and_hms(2025, 3, 30, 1, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn on_ambiguous() {
+        // 02:30 exists in CEST and CET, earliest one is used
+        assert_eq!(on(10, 26), Localized::Ambiguous(Utc.with_ymd_and_hms(2025, 10, 26, 0, 30, 0).unwrap()));
+    }
 }
@@ -0,0 +1,145 @@
+//! shift and limit computed timers, e.g. to turn lights on some time after sunrise but never before a certain time
+
+use super::day;
+use crate::time::Time;
+
+/// first and last minute of a day
+const DAY_START: i16 = 0;
+const DAY_END: i16 = 24 * 60 - 1;
+const MINUTES_PER_DAY: i16 = 24 * 60;
+
+/// applied to the computed timers of every day after averaging, in this order:
+/// offsets, photoperiod limits and finally the earliest and latest times
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Adjustment {
+    /// Minutes to turn on later than computed, negative meaning earlier
+    #[schema(example = 30)]
+    pub on_offset_minutes: i16,
+    /// Minutes to turn off later than computed, negative meaning earlier
+    #[schema(example = -15)]
+    pub off_offset_minutes: i16,
+    /// Minimum minutes to be on, extending the photoperiod equally on both ends
+    #[schema(example = 600)]
+    pub min_photoperiod_minutes: Option<u16>,
+    /// Maximum minutes to be on, shortening the photoperiod equally on both ends
+    #[schema(example = 840)]
+    pub max_photoperiod_minutes: Option<u16>,
+    /// Time to never turn on before
+    pub earliest_on_time: Option<Time>,
+    /// Time to never turn off after
+    pub latest_off_time: Option<Time>,
+}
+
+impl Adjustment {
+    /// adjusted computed timer of a day, which has a single interval.
+    /// limited to the borders of the day, unless the interval spans midnight already.
+    /// fails if nothing of the photoperiod is left.
+    pub fn apply(&self, timer: &day::Timer) -> Result<day::Timer, String> {
+        let (Some(on_time), Some(off_time)) = (timer.on_time(), timer.off_time()) else {
+            return Ok(timer.clone());
+        };
+        let spans_midnight = timer.spans_midnight();
+        let mut on = on_time.minutes() + self.on_offset_minutes;
+        // minutes since midnight of the day it turns on
+        let mut off = off_time.minutes() + self.off_offset_minutes + if spans_midnight { MINUTES_PER_DAY } else { 0 };
+
+        let length = off - on;
+        let min_length = self.min_photoperiod_minutes.map_or(length, |minutes| length.max(minutes.try_into().unwrap()));
+        let limited_length = self.max_photoperiod_minutes.map_or(min_length, |minutes| min_length.min(minutes.try_into().unwrap()));
+        if limited_length != length {
+            let center = on + length / 2;
+            on = center - limited_length / 2;
+            off = on + limited_length;
+        }
+
+        if spans_midnight {
+            if let Some(earliest_on_time) = self.earliest_on_time {
+                on = on.max(earliest_on_time.minutes());
+            }
+            if let Some(latest_off_time) = self.latest_off_time {
+                // on the following day if before turning on
+                let latest_off = latest_off_time.minutes();
+                off = off.min(if latest_off <= on { latest_off + MINUTES_PER_DAY } else { latest_off });
+            }
+            // keep at least a minute off for on and off time to differ
+            off = off.min(on + DAY_END);
+        } else {
+            on = on.max(self.earliest_on_time.map_or(DAY_START, Time::minutes)).max(DAY_START);
+            off = off.min(self.latest_off_time.map_or(DAY_END, Time::minutes)).min(DAY_END);
+        }
+        if on >= off {
+            return Err(format!("Adjusting timer {timer} leaves no photoperiod"));
+        }
+        Ok(day::Timer::new(Time::from_minutes(on).wrapped(), Time::from_minutes(off).wrapped()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computed() -> day::Timer {
+        day::Timer::new(Time::new(7, 0), Time::new(19, 0))
+    }
+
+    #[test]
+    fn none() {
+        assert_eq!(Adjustment::default().apply(&computed()), Ok(computed()));
+    }
+
+    #[test]
+    fn offsets() {
+        let adjustment = Adjustment { on_offset_minutes: 30, off_offset_minutes: -15, ..Adjustment::default() };
+        assert_eq!(adjustment.apply(&computed()), Ok(day::Timer::new(Time::new(7, 30), Time::new(18, 45))));
+    }
+
+    #[test]
+    fn photoperiod() {
+        let min = Adjustment { min_photoperiod_minutes: Some(14 * 60), ..Adjustment::default() };
+        assert_eq!(min.apply(&computed()), Ok(day::Timer::new(Time::new(6, 0), Time::new(20, 0))));
+        let max = Adjustment { max_photoperiod_minutes: Some(10 * 60), ..Adjustment::default() };
+        assert_eq!(max.apply(&computed()), Ok(day::Timer::new(Time::new(8, 0), Time::new(18, 0))));
+    }
+
+    #[test]
+    fn clamp_after_photoperiod() {
+        let adjustment = Adjustment {
+            min_photoperiod_minutes: Some(14 * 60),
+            earliest_on_time: Some(Time::new(7, 0)),
+            latest_off_time: Some(Time::new(21, 0)),
+            ..Adjustment::default()
+        };
+        assert_eq!(adjustment.apply(&computed()), Ok(day::Timer::new(Time::new(7, 0), Time::new(20, 0))));
+    }
+
+    #[test]
+    fn day_borders() {
+        let adjustment = Adjustment { on_offset_minutes: -8 * 60, off_offset_minutes: 8 * 60, ..Adjustment::default() };
+        assert_eq!(adjustment.apply(&computed()), Ok(day::Timer::new(Time::new(0, 0), Time::new(23, 59))));
+    }
+
+    #[test]
+    fn over_midnight() {
+        // e.g. from blending natural_clock
+        let computed = day::Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let timer = |adjustment: Adjustment| adjustment.apply(&computed).unwrap();
+        assert_eq!(timer(Adjustment::default()), computed);
+        assert_eq!(timer(Adjustment { on_offset_minutes: 30, off_offset_minutes: -30, ..Adjustment::default() }),
+            day::Timer::new(Time::new(20, 30), Time::new(3, 30)));
+        assert_eq!(timer(Adjustment { max_photoperiod_minutes: Some(6 * 60), ..Adjustment::default() }),
+            day::Timer::new(Time::new(21, 0), Time::new(3, 0)));
+        assert_eq!(timer(Adjustment { min_photoperiod_minutes: Some(24 * 60), ..Adjustment::default() }),
+            day::Timer::new(Time::new(12, 0), Time::new(11, 59)));
+        assert_eq!(timer(Adjustment { earliest_on_time: Some(Time::new(21, 0)), latest_off_time: Some(Time::new(2, 0)), ..Adjustment::default() }),
+            day::Timer::new(Time::new(21, 0), Time::new(2, 0)));
+        assert_eq!(timer(Adjustment { latest_off_time: Some(Time::new(22, 0)), ..Adjustment::default() }),
+            day::Timer::new(Time::new(20, 0), Time::new(22, 0)));
+    }
+
+    #[test]
+    fn nothing_left() {
+        let adjustment = Adjustment { earliest_on_time: Some(Time::new(20, 0)), ..Adjustment::default() };
+        assert!(adjustment.apply(&computed()).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! program to progressively shorten the photoperiod for species that brumate (or hibernate),
+//! hold it and lengthen it back, layered on top of the computed timers
+
+use chrono::{Days, NaiveDate};
+
+use super::day;
+use crate::time::Time;
+
+/// part of a brumation program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Photoperiod is shortened towards the target
+    RampDown,
+    /// Photoperiod is held at the target
+    Hold,
+    /// Photoperiod is lengthened back to the computed one
+    RampUp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+pub struct Program {
+    /// First local date of shortening the photoperiod
+    #[schema(value_type = String, example = "2025-11-01")]
+    pub start: NaiveDate,
+    /// Weeks to shorten the photoperiod to the target
+    #[schema(example = 3)]
+    pub ramp_down_weeks: u8,
+    /// Weeks to hold the target photoperiod
+    #[schema(example = 8)]
+    pub hold_weeks: u8,
+    /// Weeks to lengthen the photoperiod back to the computed one
+    #[schema(example = 3)]
+    pub ramp_up_weeks: u8,
+    /// Minutes to shorten the photoperiod to. Computed photoperiods already shorter are kept.
+    #[schema(example = 480)]
+    pub target_photoperiod_minutes: u16,
+}
+
+impl Program {
+    /// first local date after the program
+    pub fn end(&self) -> NaiveDate {
+        self.start + Days::new(7 * (u64::from(self.ramp_down_weeks) + u64::from(self.hold_weeks) + u64::from(self.ramp_up_weeks)))
+    }
+
+    /// phase on given date and how far the photoperiod is shortened towards the target on it,
+    /// from 0.0 (not at all) to 1.0 (target reached). `None` outside the program.
+    pub fn phase_on(&self, date: NaiveDate) -> Option<(Phase, f32)> {
+        let day = u32::try_from((date - self.start).num_days()).ok()?;
+        let ramp_down_days = 7 * u32::from(self.ramp_down_weeks);
+        let hold_days = 7 * u32::from(self.hold_weeks);
+        let ramp_up_days = 7 * u32::from(self.ramp_up_weeks);
+
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = |day: u32, days: u32| (day + 1) as f32 / days as f32;
+        if day < ramp_down_days {
+            Some((Phase::RampDown, fraction(day, ramp_down_days)))
+        } else if day < ramp_down_days + hold_days {
+            Some((Phase::Hold, 1.))
+        } else if day < ramp_down_days + hold_days + ramp_up_days {
+            // the computed photoperiod is reached on the first day after the program
+            let day = day - ramp_down_days - hold_days;
+            Some((Phase::RampUp, 1. - fraction(day, ramp_up_days + 1)))
+        } else {
+            None
+        }
+    }
+
+    /// computed day timer of given date with the photoperiod shortened according to the program,
+    /// `None` outside the program
+    pub fn apply(&self, day_timer: &day::Timer, date: NaiveDate) -> Option<day::Timer> {
+        let (_, fraction) = self.phase_on(date)?;
+        let length = day_timer.length();
+        let target = Time::from_minutes(self.target_photoperiod_minutes.try_into().unwrap());
+        if length <= target {
+            return Some(day_timer.clone());
+        }
+        Some(day_timer.shortened_to(length - ((length - target) * fraction)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program() -> Program {
+        Program {
+            start: NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+            ramp_down_weeks: 1,
+            hold_weeks: 2,
+            ramp_up_weeks: 1,
+            target_photoperiod_minutes: 8 * 60,
+        }
+    }
+
+    fn date(days: u64) -> NaiveDate {
+        program().start + Days::new(days)
+    }
+
+    #[test]
+    fn phases() {
+        assert_eq!(program().phase_on(date(0).pred_opt().unwrap()), None);
+        assert_eq!(program().phase_on(date(6)), Some((Phase::RampDown, 1.)));
+        assert_eq!(program().phase_on(date(7)), Some((Phase::Hold, 1.)));
+        assert_eq!(program().phase_on(date(20)), Some((Phase::Hold, 1.)));
+        assert!(matches!(program().phase_on(date(27)), Some((Phase::RampUp, fraction)) if fraction > 0.));
+        assert_eq!(program().phase_on(date(28)), None);
+        assert_eq!(program().end(), date(28));
+    }
+
+    #[test]
+    fn apply() {
+        let computed = day::Timer::new(Time::new(6, 0), Time::new(20, 0));
+        assert_eq!(program().apply(&computed, date(10)), Some(day::Timer::new(Time::new(9, 0), Time::new(17, 0))));
+        assert_eq!(program().apply(&computed, date(28)), None);
+
+        let short = day::Timer::new(Time::new(10, 0), Time::new(16, 0));
+        assert_eq!(program().apply(&short, date(10)), Some(short));
+    }
+}
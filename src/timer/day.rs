@@ -1,7 +1,11 @@
+use chrono::NaiveDate;
+
 use crate::time::Time;
 
+/// interval to turn the plug on and off again.
+/// if `off_time` is before `on_time`, the plug is turned off on the following day.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
-pub struct Timer {
+pub struct Interval {
     /// Time to turn the plug on
     #[schema(default = json!(Time::new(8, 0)))]
     on_time: Time,
@@ -10,7 +14,7 @@ pub struct Timer {
     off_time: Time,
 }
 
-impl Timer {
+impl Interval {
     /// with times to turn the plug on/off
     pub fn new(on_time: Time, off_time: Time) -> Self {
         assert_ne!(on_time, off_time);
@@ -24,10 +28,328 @@ impl Timer {
     pub const fn off_time(&self) -> &Time {
         &self.off_time
     }
+
+    /// whether the plug is turned off on the day after it is turned on
+    pub fn spans_midnight(&self) -> bool {
+        self.off_time < self.on_time
+    }
+
+    /// date to turn the plug off, given the date to turn it on
+    pub fn off_date(&self, date: NaiveDate) -> NaiveDate {
+        if self.spans_midnight() {
+            date.succ_opt().unwrap()
+        } else {
+            date
+        }
+    }
+
+    /// whether the plug is on at given time of the day it is turned on
+    fn covers(&self, time: Time) -> bool {
+        self.on_time <= time && (self.spans_midnight() || time < self.off_time)
+    }
+}
+
+/// intervals to turn the plug on/off during a day, ordered and not overlapping.
+/// only the last interval might span midnight. without intervals, the plug is off the whole day.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "Fields", into = "Fields")]
+pub struct Timer {
+    intervals: Vec<Interval>,
+}
+
+/// times to turn the plug on/off during a day, as (de)serialized.
+/// `on_time` and `off_time` span all intervals, which keeps state files
+/// from before multiple intervals were supported readable.
+#[derive(utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+struct Fields {
+    /// Time to turn the plug on first, missing if it is off the whole day
+    #[schema(default = json!(Time::new(8, 0)))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_time: Option<Time>,
+    /// Time to turn the plug off last, missing if it is off the whole day
+    #[schema(default = json!(Time::new(18, 0)))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    off_time: Option<Time>,
+    /// Intervals to turn the plug on/off, ordered and not overlapping. Only the last one might span midnight.
+    /// If missing, `on_time` and `off_time` form the only interval. If empty, the plug is off the whole day.
+    intervals: Option<Vec<Interval>>,
+}
+
+impl TryFrom<Fields> for Timer {
+    type Error = String;
+    fn try_from(fields: Fields) -> Result<Self, Self::Error> {
+        match (fields.intervals, fields.on_time, fields.off_time) {
+            (Some(intervals), _, _) => Self::from_intervals(intervals),
+            (None, Some(on_time), Some(off_time)) if on_time != off_time => Ok(Self::new(on_time, off_time)),
+            (None, Some(_), Some(_)) => Err("on_time and off_time must not be equal".to_string()),
+            (None, _, _) => Err("either intervals or on_time and off_time are required".to_string()),
+        }
+    }
+}
+
+impl From<Timer> for Fields {
+    fn from(timer: Timer) -> Self {
+        Self { on_time: timer.on_time().copied(), off_time: timer.off_time().copied(), intervals: Some(timer.intervals) }
+    }
+}
+
+// document the serialized fields instead of the actual ones
+impl utoipa::PartialSchema for Timer {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        Fields::schema()
+    }
+}
+impl utoipa::ToSchema for Timer {
+    fn schemas(schemas: &mut Vec<(String, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>)>) {
+        Fields::schemas(schemas);
+    }
+}
+
+impl Timer {
+    /// with times to turn the plug on/off once
+    pub fn new(on_time: Time, off_time: Time) -> Self {
+        Self { intervals: vec![Interval::new(on_time, off_time)] }
+    }
+
+    /// off the whole day
+    pub const fn off() -> Self {
+        Self { intervals: Vec::new() }
+    }
+
+    /// with multiple intervals, which need to be ordered and not overlapping.
+    /// only the last interval might span midnight, but not into the first one.
+    pub fn from_intervals(intervals: Vec<Interval>) -> Result<Self, String> {
+        let (Some(first), Some(last)) = (intervals.first(), intervals.last()) else {
+            return Ok(Self::off());
+        };
+
+        for pair in intervals.windows(2) {
+            if pair[0].spans_midnight() {
+                return Err(format!("only the last interval may span midnight, but {} does", pair[0]));
+            }
+            if pair[0].off_time >= pair[1].on_time {
+                return Err(format!("intervals must be ordered and not overlap, but {} is followed by {}", pair[0], pair[1]));
+            }
+        }
+        if intervals.len() > 1 && last.spans_midnight() && last.off_time > first.on_time {
+            return Err(format!("last interval {last} overlaps first interval {first}"));
+        }
+
+        Ok(Self { intervals })
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// time to turn the plug on first, `None` if it is off the whole day
+    pub fn on_time(&self) -> Option<&Time> {
+        self.intervals.first().map(Interval::on_time)
+    }
+
+    /// time to turn the plug off last, `None` if it is off the whole day
+    pub fn off_time(&self) -> Option<&Time> {
+        self.intervals.last().map(Interval::off_time)
+    }
+
+    /// whether the plug is turned off on the day after it is turned on for the last time
+    pub fn spans_midnight(&self) -> bool {
+        self.intervals.last().is_some_and(Interval::spans_midnight)
+    }
+
+    /// time between turning the plug on first and off last
+    pub fn length(&self) -> Time {
+        match (self.on_time(), self.off_time()) {
+            (Some(on_time), Some(off_time)) => (*off_time - *on_time).wrapped(),
+            _ => Time::new(0, 0),
+        }
+    }
+
+    /// shortened to at most given length by turning on later and off earlier by the same amount,
+    /// dropping intervals that are cut off completely
+    pub fn shortened_to(&self, length: Time) -> Self {
+        let Some(&first_on_time) = self.on_time() else {
+            return self.clone();
+        };
+        let cut = ((self.length() - length) / 2.).minutes();
+        if cut <= 0 {
+            return self.clone();
+        }
+        // in minutes after turning on first
+        let (start, end) = (cut, self.length().minutes() - cut);
+
+        let intervals = self.intervals.iter()
+            .filter_map(|interval| {
+                let on = (interval.on_time - first_on_time).wrapped().minutes();
+                let off = on + (interval.off_time - interval.on_time).wrapped().minutes();
+                let (on, off) = (on.max(start), off.min(end));
+                (on < off).then(|| Interval::new(
+                    (first_on_time + Time::from_minutes(on)).wrapped(),
+                    (first_on_time + Time::from_minutes(off)).wrapped(),
+                ))
+            })
+            .collect();
+        // cutting ordered intervals keeps them ordered
+        Self { intervals }
+    }
+
+    /// on exactly while this timer is off.
+    /// if it is off the whole day, on until the end of the day.
+    pub fn inverted(&self) -> Self {
+        let (Some(first), Some(last)) = (self.intervals.first(), self.intervals.last()) else {
+            return Self::new(Time::new(0, 0), Time::new(23, 59));
+        };
+        let mut intervals = self.intervals.windows(2)
+            .map(|pair| Interval::new(pair[0].off_time, pair[1].on_time))
+            .collect::<Vec<_>>();
+        // between turning off last and on first again, which is in the morning if this timer spans midnight
+        let wrapping = Interval::new(last.off_time, first.on_time);
+        if last.spans_midnight() {
+            intervals.insert(0, wrapping);
+        } else {
+            intervals.push(wrapping);
+        }
+        Self { intervals }
+    }
+
+    /// whether the plug is supposed to be on at given time of day, assuming the same timer every day.
+    /// if the timer spans midnight, the plug is on over midnight.
+    pub fn is_on_at(&self, time: Time) -> bool {
+        let spanning = self.intervals.last().filter(|last| last.spans_midnight());
+        spanning.is_some_and(|last| time < last.off_time) || self.covers(time)
+    }
+
+    /// whether the plug is on at given time of the day of this timer,
+    /// ignoring the interval of the previous day possibly spanning midnight
+    pub fn covers(&self, time: Time) -> bool {
+        self.intervals.iter().any(|interval| interval.covers(time))
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} - {}", self.on_time, self.off_time)
+    }
 }
 
 impl std::fmt::Display for Timer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{ on: {}, off: {} }}", self.on_time, self.off_time)
+        match self.intervals.as_slice() {
+            [] => return write!(f, "{{ off }}"),
+            [interval] => return write!(f, "{{ on: {}, off: {} }}", interval.on_time, interval.off_time),
+            _ => {},
+        }
+        let intervals = self.intervals.iter().map(ToString::to_string).collect::<Vec<_>>();
+        write!(f, "{{ {} }}", intervals.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(on_hour: i8, off_hour: i8) -> Interval {
+        Interval::new(Time::new(on_hour, 0), Time::new(off_hour, 0))
+    }
+
+    #[test]
+    fn is_on_at() {
+        let timer = Timer::new(Time::new(8, 0), Time::new(20, 0));
+        assert!(!timer.is_on_at(Time::new(7, 59)));
+        assert!(timer.is_on_at(Time::new(8, 0)));
+        assert!(timer.is_on_at(Time::new(19, 59)));
+        assert!(!timer.is_on_at(Time::new(20, 0)));
+    }
+
+    #[test]
+    fn is_on_at_over_midnight() {
+        let timer = Timer::new(Time::new(20, 0), Time::new(8, 0));
+        assert!(timer.is_on_at(Time::new(23, 0)));
+        assert!(timer.is_on_at(Time::new(0, 0)));
+        assert!(!timer.is_on_at(Time::new(8, 0)));
+        assert!(!timer.is_on_at(Time::new(12, 0)));
+    }
+
+    #[test]
+    fn is_on_at_with_siesta() {
+        let timer = Timer::from_intervals(vec![interval(8, 12), interval(14, 20)]).unwrap();
+        assert!(timer.is_on_at(Time::new(11, 59)));
+        assert!(!timer.is_on_at(Time::new(12, 0)));
+        assert!(!timer.is_on_at(Time::new(13, 59)));
+        assert!(timer.is_on_at(Time::new(14, 0)));
+    }
+
+    #[test]
+    fn spans_midnight() {
+        let timer = Timer::new(Time::new(20, 0), Time::new(4, 0));
+        let date = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        assert!(timer.spans_midnight());
+        assert_eq!(timer.length(), Time::new(8, 0));
+        assert_eq!(timer.intervals()[0].off_date(date), NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+
+        let timer = Timer::new(Time::new(8, 0), Time::new(20, 0));
+        assert!(!timer.spans_midnight());
+        assert_eq!(timer.length(), Time::new(12, 0));
+        assert_eq!(timer.intervals()[0].off_date(date), date);
+    }
+
+    #[test]
+    fn from_intervals() {
+        assert_eq!(Timer::from_intervals(vec![]), Ok(Timer::off()));
+        assert!(Timer::from_intervals(vec![interval(8, 12), interval(14, 20)]).is_ok());
+        assert!(Timer::from_intervals(vec![interval(8, 12), interval(20, 4)]).is_ok());
+        // unordered
+        assert!(Timer::from_intervals(vec![interval(14, 20), interval(8, 12)]).is_err());
+        // overlapping
+        assert!(Timer::from_intervals(vec![interval(8, 14), interval(12, 20)]).is_err());
+        assert!(Timer::from_intervals(vec![interval(8, 12), interval(12, 20)]).is_err());
+        // spanning midnight into first interval
+        assert!(Timer::from_intervals(vec![interval(8, 12), interval(20, 9)]).is_err());
+        // spanning midnight before last interval
+        assert!(Timer::from_intervals(vec![interval(20, 4), interval(22, 23)]).is_err());
+    }
+
+    #[test]
+    fn inverted() {
+        let timer = Timer::from_intervals(vec![interval(8, 12), interval(14, 20)]).unwrap();
+        assert_eq!(timer.inverted(), Timer::from_intervals(vec![interval(12, 14), interval(20, 8)]).unwrap());
+        let timer = Timer::from_intervals(vec![interval(8, 12), interval(20, 4)]).unwrap();
+        assert_eq!(timer.inverted(), Timer::from_intervals(vec![interval(4, 8), interval(12, 20)]).unwrap());
+    }
+
+    #[test]
+    fn off() {
+        let timer = Timer::off();
+        assert!(!timer.is_on_at(Time::new(12, 0)));
+        assert!(!timer.spans_midnight());
+        assert_eq!(timer.on_time(), None);
+        assert_eq!(timer.inverted(), Timer::new(Time::new(0, 0), Time::new(23, 59)));
+        assert_eq!(serde_json::to_string(&timer).unwrap(), r#"{"intervals":[]}"#);
+        assert_eq!(serde_json::from_str::<Timer>(r#"{"intervals":[]}"#).unwrap(), timer);
+    }
+
+    #[test]
+    fn shortened_to() {
+        let timer = Timer::new(Time::new(8, 0), Time::new(20, 0));
+        assert_eq!(timer.shortened_to(Time::new(8, 0)), Timer::new(Time::new(10, 0), Time::new(18, 0)));
+        assert_eq!(timer.shortened_to(Time::new(14, 0)), timer);
+
+        let timer = Timer::from_intervals(vec![interval(8, 10), interval(12, 22)]).unwrap();
+        assert_eq!(timer.shortened_to(Time::new(6, 0)), Timer::new(Time::new(12, 0), Time::new(18, 0)));
+    }
+
+    #[test]
+    fn deserialize_single_interval() {
+        let timer: Timer = serde_json::from_str(r#"{"on_time":{"hour":8,"minute":0},"off_time":{"hour":20,"minute":0}}"#).unwrap();
+        assert_eq!(timer, Timer::new(Time::new(8, 0), Time::new(20, 0)));
+    }
+
+    #[test]
+    fn serialize_roundtrip() {
+        let timer = Timer::from_intervals(vec![interval(8, 12), interval(14, 20)]).unwrap();
+        let json = serde_json::to_value(&timer).unwrap();
+        assert_eq!(json["on_time"]["hour"], 8);
+        assert_eq!(json["off_time"]["hour"], 20);
+        assert_eq!(serde_json::from_value::<Timer>(json).unwrap(), timer);
     }
 }
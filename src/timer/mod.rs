@@ -1,2 +1,4 @@
 pub mod day;
 pub mod year;
+pub mod adjustment;
+pub mod brumation;
@@ -1,54 +1,241 @@
-use chrono::{DateTime, NaiveDate, Datelike, Utc};
+use chrono::{NaiveDate, Datelike};
 use reqwest::StatusCode;
 use chrono_tz::Tz;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 use super::day;
+use super::adjustment::Adjustment;
+use super::brumation;
 use crate::time::Time;
 use crate::api::WebResponse;
 use crate::sunrise_api::APIResponseDay;
 
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Timer {
     /// includes leap day
     #[serde(with = "serde_big_array::BigArray")]
-    day_timers: [day::Timer; 366]
+    day_timers: [day::Timer; 366],
+    /// replace the day timers of specific dates
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    overrides: BTreeMap<NaiveDate, day::Timer>,
+    /// replace the day timers of consecutive dates while gradually moving to them from previous ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    transition: BTreeMap<NaiveDate, day::Timer>,
+    /// shorten the photoperiod of the day timers for some weeks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    brumation: Option<brumation::Program>,
 }
 
-/// intermediary representation for calculations
+/// how to combine local days and days of the natural habitat into computed timers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Blending {
+    /// Average day lengths, keeping the local center of the day
+    #[default]
+    LocalCenter,
+    /// Average sunrise and sunset times independently, using natural times on the clock of the natural habitat
+    Independent,
+    /// Average day lengths, shifting the local center of the day by the offset
+    /// of the natural solar noon from 12:00 on the clock of the natural habitat
+    SolarNoonOffset,
+    /// Average sunrise and sunset times independently, converting natural times to the local timezone.
+    /// Timers might span midnight.
+    NaturalClock,
+}
+
+/// which times of a day define turning on and off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, utoipa::ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Twilight {
+    /// Sunrise and sunset
+    #[default]
+    Sunrise,
+    /// Dawn and dusk, the start and end of civil twilight
+    Civil,
+    /// First and last light, when the sky starts getting light and gets completely dark
+    FirstLight,
+    /// End of the golden hour in the morning and its start in the evening
+    GoldenHour,
+}
+
+/// intermediary representation for calculations.
+/// sunrise and sunset are the times of the chosen twilight.
 #[derive(PartialEq)]
 struct LocalDay {
     length: Time,
     /// exactly in between sunrise and sunset
     center: Time,
+    sunrise: Time,
+    sunset: Time,
+    /// in minutes
+    utc_offset: i16,
+}
+
+/// intermediary representation for calculations, with times on the clock of the natural habitat.
+/// sunrise and sunset are the times of the chosen twilight.
+struct NaturalDay {
+    length: Time,
+    sunrise: Time,
+    sunset: Time,
+    solar_noon: Time,
+    /// in minutes
+    utc_offset: i16,
 }
 
 impl Timer {
     /// day timers include leap day
     #[allow(clippy::large_types_passed_by_value)]
     pub const fn new(day_timers: [day::Timer; 366]) -> Self {
-        Self { day_timers }
+        Self { day_timers, overrides: BTreeMap::new(), transition: BTreeMap::new(), brumation: None }
     }
 
+    /// without overrides
     pub const fn day_timers(&self) -> &[day::Timer; 366] {
         &self.day_timers
     }
 
-    pub fn for_today(&self, timezone: Tz) -> &day::Timer {
-        let now = Utc::now().with_timezone(&timezone);
+    pub const fn overrides(&self) -> &BTreeMap<NaiveDate, day::Timer> {
+        &self.overrides
+    }
+
+    /// replace day timer of given date, returns the previous override if any
+    pub fn set_override(&mut self, date: NaiveDate, day_timer: day::Timer) -> Option<day::Timer> {
+        self.overrides.insert(date, day_timer)
+    }
+
+    /// use day timer of given date again, returns the removed override if any
+    pub fn remove_override(&mut self, date: NaiveDate) -> Option<day::Timer> {
+        self.overrides.remove(&date)
+    }
+
+    pub const fn brumation(&self) -> Option<&brumation::Program> {
+        self.brumation.as_ref()
+    }
+
+    /// replace brumation program, returns the previous one if any
+    pub fn set_brumation(&mut self, program: Option<brumation::Program>) -> Option<brumation::Program> {
+        std::mem::replace(&mut self.brumation, program)
+    }
+
+    /// take over overrides and brumation program of another year timer, e.g. when reconfiguring
+    pub fn keep_layers_of(&mut self, other: &Self) {
+        self.overrides.clone_from(&other.overrides);
+        self.brumation = other.brumation;
+    }
+
+    /// considering overrides, brumation program and transition
+    pub fn for_today(&self, timezone: Tz) -> Cow<'_, day::Timer> {
+        self.for_date(Time::today(timezone))
+    }
+
+    /// day timers of the 366 days starting on given date by their index like `day_timers`, as returned by `for_date`.
+    /// the leap day entry is left as is if the leap day is not among them.
+    pub fn upcoming_day_timers(&self, from: NaiveDate) -> [day::Timer; 366] {
+        let mut day_timers = self.day_timers.clone();
+        // backwards for earlier dates to take precedence over later ones of the same index
+        for days in (0 .. 366).rev() {
+            let date = from + chrono::Days::new(days);
+            day_timers[Self::index(date)] = self.for_date(date).into_owned();
+        }
+        day_timers
+    }
+
+    /// considering overrides, brumation program and transition
+    pub fn for_date(&self, date: NaiveDate) -> Cow<'_, day::Timer> {
+        if let Some(day_timer) = self.overrides.get(&date) {
+            return Cow::Borrowed(day_timer);
+        }
+        let day_timer = self.scheduled_for(date);
+        self.brumation
+            .and_then(|program| program.apply(day_timer, date))
+            .map_or(Cow::Borrowed(day_timer), Cow::Owned)
+    }
+
+    /// considering the transition, but not overrides
+    fn scheduled_for(&self, date: NaiveDate) -> &day::Timer {
+        self.transition.get(&date).unwrap_or(&self.day_timers[Self::index(date)])
+    }
+
+    /// interpolated day timers of the dates of the transition, if any
+    pub const fn transition(&self) -> &BTreeMap<NaiveDate, day::Timer> {
+        &self.transition
+    }
+
+    /// move gradually from the day timers of `previous` to these ones over given number of days from `start` on,
+    /// by linearly interpolating the day timers of the days in between.
+    /// on and off times move the shorter way around midnight. fails if they can't be interpolated.
+    pub fn transition_from(&mut self, previous: &Self, start: NaiveDate, days: u16) -> Result<(), String> {
+        self.transition = start.iter_days()
+            // the last day already uses these day timers
+            .take(usize::from(days.saturating_sub(1)))
+            .enumerate()
+            .map(|(day, date)| {
+                let from = previous.scheduled_for(date);
+                let to = &self.day_timers[Self::index(date)];
+                #[allow(clippy::cast_precision_loss)]
+                let fraction = (day + 1) as f32 / f32::from(days);
+                Ok((date, Self::interpolate_day_timers(from, to, fraction)?))
+            })
+            // return the first error if present
+            .collect::<Result<_, String>>()?;
+        Ok(())
+    }
 
-        &self.day_timers[Self::index(now)]
+    /// fewest days to move from the day timers of `previous` to these ones from `start` on,
+    /// without moving any on or off time by more than `max_minutes` per day. at most a year.
+    pub fn transition_days(&self, previous: &Self, start: NaiveDate, max_minutes: u16) -> u16 {
+        // largest difference of any on or off time on given date
+        let difference = |date| {
+            let from = previous.scheduled_for(date);
+            let to = &self.day_timers[Self::index(date)];
+            from.intervals().iter().zip(to.intervals())
+                .flat_map(|(from, to)| [
+                    Self::difference(*from.on_time(), *to.on_time()),
+                    Self::difference(*from.off_time(), *to.off_time()),
+                ])
+                .map(|difference| difference.unsigned_abs())
+                .max()
+                .unwrap_or_default()
+        };
+        (1 ..= 366)
+            .find(|&days| start.iter_days().take(usize::from(days))
+                .all(|date| u32::from(difference(date)) <= u32::from(max_minutes) * u32::from(days)))
+            .unwrap_or(366)
+    }
+
+    /// day timer `fraction` of the way from `from` to `to`, which need the same number of intervals
+    fn interpolate_day_timers(from: &day::Timer, to: &day::Timer, fraction: f32) -> Result<day::Timer, String> {
+        if from.intervals().len() != to.intervals().len() {
+            return Err(format!("Can't move gradually from {from} to {to}, as they have a different number of intervals"));
+        }
+        let intervals = from.intervals().iter()
+            .zip(to.intervals())
+            .map(|(from, to)| {
+                let on = Self::interpolate(*from.on_time(), *to.on_time(), fraction);
+                let off = Self::interpolate(*from.off_time(), *to.off_time(), fraction);
+                if on == off {
+                    return Err(format!("interpolated interval between {from} and {to} is empty"));
+                }
+                Ok(day::Interval::new(on, off))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        day::Timer::from_intervals(intervals)
     }
 
     /// if `Ok`, returns tuple of
     /// - local timezone
-    /// - actual year timer (given `natural_factor`)
+    /// - actual year timer (given `natural_factors` of every day)
     /// - local year timer (`natural_factor == 0.0`)
     /// - natural year timer (`natural_factor == 1.0`)
-    pub fn from_api_days_average(natural_factor: f32, local_api_days: &[APIResponseDay], natural_api_days: &[APIResponseDay])
-        -> WebResponse<(Tz, Self, Self, Self)>
-    {
-        assert!(natural_factor >= 0.);
-        assert!(natural_factor <= 1.);
+    pub fn from_api_days_average(
+        natural_factors: &[f32; 366],
+        blending: Blending,
+        twilight: Twilight,
+        local_api_days: &[APIResponseDay],
+        natural_api_days: &[APIResponseDay],
+    ) -> WebResponse<(Tz, Self, Self, Self)> {
+        assert!(natural_factors.iter().all(|natural_factor| (0. ..= 1.).contains(natural_factor)));
         assert_eq!(local_api_days.len(), 366);
         assert_eq!(natural_api_days.len(), 366);
 
@@ -58,27 +245,25 @@ impl Timer {
 
         let local_days = local_api_days.iter()
             .map(|local_item| -> WebResponse<LocalDay> {
-                let day_length = Self::map_api_day_field(local_item.day_length.clone())?;
-                let Ok(length) = Time::from_hhmmss(&day_length) else {
-                    return Err((StatusCode::BAD_REQUEST, String::from("Local day length could not be parsed, coordinates might be too close to a polar region")));
-                };
-                let center = {
-                    let sunrise = Self::map_api_day_field(local_item.sunrise.clone())?;
-                    let sunrise = Time::from_military(&sunrise);
-                    let sunset = Self::map_api_day_field(local_item.sunset.clone())?;
-                    let sunset = Time::from_military(&sunset);
-                    ((sunset - sunrise) / 2.0) + sunrise
-                };
-                Ok(LocalDay { length, center })
+                let (length, sunrise, sunset) = Self::map_twilight(local_item, twilight, "Local")?;
+                let center = ((sunset - sunrise) / 2.0) + sunrise;
+                let utc_offset = Self::map_utc_offset(local_item.utc_offset)?;
+                Ok(LocalDay { length, center, sunrise, sunset, utc_offset })
             })
             // return the first error if present
             .collect::<Result<Vec<_>, _>>()?;
 
-        let mut natural_day_lengths = natural_api_days.iter()
-            .map(|natural_item| -> WebResponse<Time> {
-                let day_length = Self::map_api_day_field(natural_item.day_length.clone())?;
-                Time::from_hhmmss(&day_length)
-                    .map_err(|()| (StatusCode::BAD_REQUEST, String::from("Natural day length could not be parsed, coordinates might be too close to a polar region")))
+        let mut natural_days = natural_api_days.iter()
+            .map(|natural_item| -> WebResponse<NaturalDay> {
+                let (length, sunrise, sunset) = Self::map_twilight(natural_item, twilight, "Natural")?;
+                let solar_noon = Self::map_api_day_field(natural_item.solar_noon.clone())?;
+                Ok(NaturalDay {
+                    length,
+                    sunrise,
+                    sunset,
+                    solar_noon: Time::from_military(&solar_noon),
+                    utc_offset: Self::map_utc_offset(natural_item.utc_offset)?,
+                })
             })
             // return the first error if present
             .collect::<Result<Vec<_>, _>>()?;
@@ -88,10 +273,10 @@ impl Timer {
             .unwrap();
         let local_max_index = local_days.iter().position(|d| d == local_max).unwrap();
 
-        let natural_max = natural_day_lengths.iter().max().unwrap();
-        let natural_max_index = natural_day_lengths.iter().position(|t| t == natural_max).unwrap();
+        let natural_max = natural_days.iter().map(|day| day.length).max().unwrap();
+        let natural_max_index = natural_days.iter().position(|day| day.length == natural_max).unwrap();
 
-        // shift natural day lengths to ensure
+        // shift natural days to ensure
         // longest natural day is at the date of the longest local day.
         // this is especially useful if local and natural location are in different hemispheres.
         // value is between -365 and 365.
@@ -100,70 +285,158 @@ impl Timer {
         log::debug!("longest day: local = {local_max_index}, natural = {natural_max_index} => shift natural by {shift}");
 
         if shift >= 0 {
-            natural_day_lengths.rotate_right(shift.try_into().unwrap());
+            natural_days.rotate_right(shift.try_into().unwrap());
         } else {
-            natural_day_lengths.rotate_left(shift.abs().try_into().unwrap());
+            natural_days.rotate_left(shift.abs().try_into().unwrap());
         }
 
         // skip averaging if possible
-        let year_timer = if natural_factor == 0. {
-            Self::from_api_days(local_api_days)?
+        let only_local = natural_factors.iter().all(|natural_factor| *natural_factor == 0.);
+        let only_natural = natural_factors.iter().all(|natural_factor| (natural_factor - 1.).abs() < f32::EPSILON);
+        let year_timer = if only_local {
+            Self::from_api_days(local_api_days, twilight)?
         } else {
-            Self::average(&local_days, &natural_day_lengths, natural_factor)
+            Self::average(&local_days, &natural_days, natural_factors, blending)?
         };
 
-        let natural_year_timer = if (natural_factor - 1.).abs() < f32::EPSILON {
-            year_timer
+        let natural_year_timer = if only_natural {
+            year_timer.clone()
         } else {
-            Self::average(&local_days, &natural_day_lengths, 1.)
+            Self::average(&local_days, &natural_days, &[1.; 366], blending)?
         };
 
         let natural_year_timer_is_valid = natural_year_timer.day_timers().iter()
-            .flat_map(|timer| [timer.on_time(), timer.off_time()])
+            .flat_map(|timer| [timer.on_time(), timer.off_time()].into_iter().flatten())
             .all(|time| time.is_valid());
         if !natural_year_timer_is_valid {
             return Err((StatusCode::BAD_REQUEST, "Computed timers exceed day borders, days are too long, coordinates might be too close to a polar region".to_string()));
         }
 
-        let local_year_timer = if natural_factor == 0. {
-            year_timer
+        let local_year_timer = if only_local {
+            year_timer.clone()
         } else {
-            Self::average(&local_days, &natural_day_lengths, 0.)
+            Self::average(&local_days, &natural_days, &[0.; 366], blending)?
         };
 
         Ok((timezone, year_timer, local_year_timer, natural_year_timer))
     }
 
-    /// compute year timer using a `natural_factor`
-    fn average(local_days: &[LocalDay], natural_day_lengths: &[Time], natural_factor: f32) -> Self {
+    /// with the day timers of every day adjusted, keeping overrides as they are
+    pub fn adjusted(&self, adjustment: &Adjustment) -> Result<Self, String> {
+        let day_timers = self.day_timers.iter()
+            .map(|day_timer| adjustment.apply(day_timer))
+            // return the first error if present
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { day_timers: day_timers.try_into().unwrap(), ..self.clone() })
+    }
+
+    /// same day timer every day
+    pub fn daily(day_timer: &day::Timer) -> Self {
+        Self::new(std::array::from_fn(|_| day_timer.clone()))
+    }
+
+    /// day timers given for the 15th of every month from january to december,
+    /// linearly interpolated for the days in between.
+    /// all of them need the same number of intervals.
+    pub fn monthly(month_timers: &[day::Timer; 12]) -> Result<Self, String> {
+        let interval_count = month_timers[0].intervals().len();
+        if month_timers.iter().any(|timer| timer.intervals().len() != interval_count) {
+            return Err("all monthly timers need the same number of intervals".to_string());
+        }
+
+        let day_timers = (0 .. 366).map(|index| {
+            let (previous, next, fraction) = Self::monthly_anchors(index);
+            Self::interpolate_day_timers(&month_timers[previous], &month_timers[next], fraction)
+        })
+        // return the first error if present
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(day_timers.try_into().unwrap()))
+    }
+
+    /// natural factors of every day, given for the 15th of every month from january to december,
+    /// linearly interpolated for the days in between
+    pub fn monthly_natural_factors(month_factors: &[f32; 12]) -> [f32; 366] {
+        std::array::from_fn(|index| {
+            let (previous, next, fraction) = Self::monthly_anchors(index);
+            month_factors[previous] + (month_factors[next] - month_factors[previous]) * fraction
+        })
+    }
+
+    /// months (from 0) with the 15th right before and after the day with given index,
+    /// wrapping around the end of the year, and the fraction of the way between them
+    fn monthly_anchors(index: usize) -> (usize, usize, f32) {
+        // indices of the 15th of every month, which are the same as in the leap year 2000
+        let anchor = |month: usize| Self::index(NaiveDate::from_ymd_opt(2000, u32::try_from(month).unwrap() + 1, 15).unwrap());
+
+        let next = (0 .. 12).find(|month| anchor(*month) > index).unwrap_or(0);
+        let previous = (next + 11) % 12;
+        let start = anchor(previous);
+        let span = (anchor(next) + 366 - start) % 366;
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = ((index + 366 - start) % 366) as f32 / span as f32;
+        (previous, next, fraction)
+    }
+
+    /// time `fraction` of the way from `from` to `to`, taking the shorter way around midnight
+    fn interpolate(from: Time, to: Time, fraction: f32) -> Time {
+        let difference = f32::from(Self::difference(from, to));
+        #[allow(clippy::cast_possible_truncation)]
+        let minutes = (difference * fraction).round() as i16;
+        (from + Time::from_minutes(minutes)).wrapped()
+    }
+
+    /// minutes from `from` to `to` the shorter way around midnight, negative if backwards
+    fn difference(from: Time, to: Time) -> i16 {
+        ((to - from).minutes() + 12 * 60).rem_euclid(24 * 60) - 12 * 60
+    }
+
+    /// compute year timer using a `natural_factor` for every day
+    fn average(local_days: &[LocalDay], natural_days: &[NaturalDay], natural_factors: &[f32; 366], blending: Blending) -> WebResponse<Self> {
         assert_eq!(local_days.len(), 366);
-        assert_eq!(natural_day_lengths.len(), 366);
+        assert_eq!(natural_days.len(), 366);
 
         let day_timers = local_days.iter()
-            .zip(natural_day_lengths.iter())
-            .map(|(local_day, natural_day_length)| {
-                let averaged_day_length = (*natural_day_length * natural_factor)
+            .zip(natural_days.iter())
+            .zip(natural_factors)
+            .map(|((local_day, natural_day), &natural_factor)| {
+                let averaged_day_length = (natural_day.length * natural_factor)
                     + (local_day.length * (1. - natural_factor));
-                let on  = local_day.center - (averaged_day_length / 2.);
-                let off = local_day.center + (averaged_day_length / 2.);
-                day::Timer::new(on, off)
+                let center = match blending {
+                    Blending::LocalCenter => local_day.center,
+                    Blending::SolarNoonOffset => local_day.center + ((natural_day.solar_noon - Time::new(12, 0)) * natural_factor),
+                    Blending::Independent | Blending::NaturalClock => {
+                        let (natural_sunrise, natural_sunset) = if blending == Blending::NaturalClock {
+                            let shift = Time::from_minutes(local_day.utc_offset - natural_day.utc_offset);
+                            ((natural_day.sunrise + shift).wrapped(), (natural_day.sunset + shift).wrapped())
+                        } else {
+                            (natural_day.sunrise, natural_day.sunset)
+                        };
+                        let on = Self::interpolate(local_day.sunrise, natural_sunrise, natural_factor);
+                        let off = Self::interpolate(local_day.sunset, natural_sunset, natural_factor);
+                        if on == off {
+                            return Err((StatusCode::BAD_REQUEST, String::from("Averaged sunrise and sunset times coincide, try another blending")));
+                        }
+                        return Ok(day::Timer::new(on, off));
+                    },
+                };
+                let on  = center - (averaged_day_length / 2.);
+                let off = center + (averaged_day_length / 2.);
+                Ok(day::Timer::new(on, off))
             })
-            .collect::<Vec<_>>();
-            
-        Self::new(day_timers.try_into().unwrap())
+            // return the first error if present
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(day_timers.try_into().unwrap()))
     }
 
-    fn from_api_days(api_days: &[APIResponseDay]) -> WebResponse<Self> {
+    fn from_api_days(api_days: &[APIResponseDay], twilight: Twilight) -> WebResponse<Self> {
         assert_eq!(api_days.len(), 366);
 
         let day_timers = api_days.iter()
             .map(|day| -> WebResponse<day::Timer> {
-                let sunrise = Self::map_api_day_field(day.sunrise.clone())?;
-                let sunset = Self::map_api_day_field(day.sunset.clone())?;
-                Ok(day::Timer::new(
-                    Time::from_military(&sunrise),
-                    Time::from_military(&sunset),
-                ))
+                let (_, on, off) = Self::map_twilight(day, twilight, "Local")?;
+                Ok(day::Timer::new(on, off))
             })
             // return the first error if present
             .collect::<Result<Vec<_>, _>>()?;
@@ -171,10 +444,10 @@ impl Timer {
         Ok(Self::new(day_timers.try_into().unwrap()))
     }
 
-    /// returns index of day timers to use for given moment in time
-    fn index(now: DateTime<Tz>) -> usize {
-        let leap_year = now.date_naive().leap_year();
-        let day = now.ordinal0();
+    /// returns index of day timers to use for given date
+    fn index(date: NaiveDate) -> usize {
+        let leap_year = date.leap_year();
+        let day = date.ordinal0();
 
         let leap_day_index = NaiveDate::from_ymd_opt(2000, 2, 29).unwrap().ordinal0();
         let mut index = day;
@@ -187,6 +460,45 @@ impl Timer {
         index.try_into().unwrap()
     }
 
+    /// map an `APIResponseDay` to the length of the day and the times it starts and ends at,
+    /// given the twilight to use and whether it is a "Local" or "Natural" one for error messages
+    fn map_twilight(day: &APIResponseDay, twilight: Twilight, location: &str) -> WebResponse<(Time, Time, Time)> {
+        let time = |field: &Option<String>| Self::map_api_day_field(field.clone()).map(|time| Time::from_military(&time));
+        // might not occur on some days, so missing ones are a problem of the chosen twilight rather than the API
+        let twilight_time = |field: &Option<String>, name: &str| field.as_deref().map(Time::from_military).ok_or_else(|| (
+            StatusCode::BAD_REQUEST,
+            format!("{location} {name} is missing on some days, coordinates might be too close to a polar region, consider another twilight"),
+        ));
+        let polar_error = || (StatusCode::BAD_REQUEST, format!("{location} day length could not be parsed, coordinates might be too close to a polar region"));
+
+        let (start, end) = match twilight {
+            Twilight::Sunrise => {
+                // more precise than the difference of sunrise and sunset
+                let day_length = Self::map_api_day_field(day.day_length.clone())?;
+                let length = Time::from_hhmmss(&day_length).map_err(|()| polar_error())?;
+                return Ok((length, time(&day.sunrise)?, time(&day.sunset)?));
+            },
+            Twilight::Civil => (twilight_time(&day.dawn, "dawn")?, twilight_time(&day.dusk, "dusk")?),
+            Twilight::FirstLight => (twilight_time(&day.first_light, "first light")?, twilight_time(&day.last_light, "last light")?),
+            Twilight::GoldenHour => {
+                // the morning golden hour ends as long before solar noon as the evening one starts after it
+                let solar_noon = time(&day.solar_noon)?;
+                let evening = twilight_time(&day.golden_hour, "golden hour")?;
+                (solar_noon - (evening - solar_noon), evening)
+            },
+        };
+        if start >= end {
+            return Err(polar_error());
+        }
+        Ok((end - start, start, end))
+    }
+
+    /// map the utc offset of an `APIResponseDay` in minutes to a `WebResponse`
+    fn map_utc_offset(utc_offset: Option<i32>) -> WebResponse<i16> {
+        Self::map_api_day_field(utc_offset)?.try_into()
+            .map_err(|_| (StatusCode::BAD_GATEWAY, String::from("Error while processing sunrise API response, a UTC offset was out of range")))
+    }
+
     /// map a field of an `APIResponseDay` to a `WebResponse`
     fn map_api_day_field<T>(option: Option<T>) -> WebResponse<T> {
         option.map_or_else(
@@ -209,10 +521,182 @@ impl std::fmt::Display for Timer {
 mod tests {
     use super::*;
 
+    fn month_timers() -> [day::Timer; 12] {
+        std::array::from_fn(|month| if month % 2 == 0 {
+            day::Timer::new(Time::new(8, 0), Time::new(20, 0))
+        } else {
+            day::Timer::new(Time::new(9, 0), Time::new(19, 0))
+        })
+    }
+
+    #[test]
+    fn overrides() {
+        let mut timer = Timer::daily(&day::Timer::new(Time::new(8, 0), Time::new(20, 0)));
+        let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let day_timer = day::Timer::new(Time::new(6, 0), Time::new(22, 0));
+
+        assert_eq!(timer.set_override(date, day_timer.clone()), None);
+        assert_eq!(*timer.for_date(date), day_timer);
+        assert_ne!(*timer.for_date(date.succ_opt().unwrap()), day_timer);
+
+        assert_eq!(timer.remove_override(date), Some(day_timer.clone()));
+        assert_ne!(*timer.for_date(date), day_timer);
+    }
+
+    #[test]
+    fn upcoming_day_timers() {
+        let scheduled = day::Timer::new(Time::new(8, 0), Time::new(20, 0));
+        let mut timer = Timer::daily(&scheduled);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let day_timer = day::Timer::new(Time::new(6, 0), Time::new(22, 0));
+        // already passed this year and same index as today next year
+        timer.set_override(NaiveDate::from_ymd_opt(2025, 6, 20).unwrap(), day_timer.clone());
+        timer.set_override(NaiveDate::from_ymd_opt(2026, 6, 21).unwrap(), day_timer.clone());
+        timer.set_override(NaiveDate::from_ymd_opt(2026, 1, 10).unwrap(), day_timer.clone());
+
+        let day_timers = timer.upcoming_day_timers(today);
+        assert_eq!(day_timers[Timer::index(today)], scheduled);
+        assert_eq!(day_timers[Timer::index(today.pred_opt().unwrap())], scheduled);
+        assert_eq!(day_timers[9], day_timer);
+    }
+
+    #[test]
+    fn transition() {
+        let previous = Timer::daily(&day::Timer::new(Time::new(8, 0), Time::new(20, 0)));
+        let mut timer = Timer::daily(&day::Timer::new(Time::new(10, 0), Time::new(18, 0)));
+        let start = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        let date = |days| start + chrono::Days::new(days);
+        assert_eq!(timer.transition_days(&previous, start, 30), 4);
+
+        timer.transition_from(&previous, start, 4).unwrap();
+        assert_eq!(timer.transition().len(), 3);
+        assert_eq!(*timer.for_date(date(0)), day::Timer::new(Time::new(8, 30), Time::new(19, 30)));
+        assert_eq!(*timer.for_date(date(2)), day::Timer::new(Time::new(9, 30), Time::new(18, 30)));
+        assert_eq!(*timer.for_date(date(3)), day::Timer::new(Time::new(10, 0), Time::new(18, 0)));
+    }
+
+    #[test]
+    fn transition_different_intervals() {
+        let previous = Timer::daily(&day::Timer::off());
+        let mut timer = Timer::daily(&day::Timer::new(Time::new(10, 0), Time::new(18, 0)));
+        let start = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        assert!(timer.transition_from(&previous, start, 4).is_err());
+    }
+
+    #[test]
+    fn blending() {
+        let local_days = std::iter::repeat_with(|| LocalDay {
+            length: Time::new(12, 0),
+            center: Time::new(12, 0),
+            sunrise: Time::new(6, 0),
+            sunset: Time::new(18, 0),
+            utc_offset: 60,
+        }).take(366).collect::<Vec<_>>();
+        // 7 hours ahead of local time
+        let natural_days = std::iter::repeat_with(|| NaturalDay {
+            length: Time::new(14, 0),
+            sunrise: Time::new(7, 0),
+            sunset: Time::new(21, 0),
+            solar_noon: Time::new(14, 0),
+            utc_offset: 480,
+        }).take(366).collect::<Vec<_>>();
+
+        let first = |blending| Timer::average(&local_days, &natural_days, &[0.5; 366], blending).unwrap().day_timers[0].clone();
+        assert_eq!(first(Blending::LocalCenter), day::Timer::new(Time::new(5, 30), Time::new(18, 30)));
+        assert_eq!(first(Blending::SolarNoonOffset), day::Timer::new(Time::new(6, 30), Time::new(19, 30)));
+        assert_eq!(first(Blending::Independent), day::Timer::new(Time::new(6, 30), Time::new(19, 30)));
+        assert_eq!(first(Blending::NaturalClock), day::Timer::new(Time::new(3, 0), Time::new(16, 0)));
+    }
+
+    #[test]
+    fn twilight() {
+        let field = |value: &str| Some(String::from(value));
+        let day = APIResponseDay {
+            date: field("2025-06-21"),
+            sunrise: field("0500"),
+            sunset: field("2130"),
+            first_light: field("0300"),
+            last_light: field("2330"),
+            dawn: field("0420"),
+            dusk: field("2210"),
+            solar_noon: field("1315"),
+            golden_hour: field("2045"),
+            day_length: field("16:30:12"),
+            timezone: field("Europe/Berlin"),
+            utc_offset: Some(120),
+        };
+
+        let times = |twilight| Timer::map_twilight(&day, twilight, "Local").unwrap();
+        assert_eq!(times(Twilight::Sunrise), (Time::new(16, 30), Time::new(5, 0), Time::new(21, 30)));
+        assert_eq!(times(Twilight::Civil), (Time::new(17, 50), Time::new(4, 20), Time::new(22, 10)));
+        assert_eq!(times(Twilight::FirstLight), (Time::new(20, 30), Time::new(3, 0), Time::new(23, 30)));
+        assert_eq!(times(Twilight::GoldenHour), (Time::new(15, 0), Time::new(5, 45), Time::new(20, 45)));
+
+        let polar = APIResponseDay { golden_hour: field("1200"), ..day.clone() };
+        assert!(Timer::map_twilight(&polar, Twilight::GoldenHour, "Local").is_err());
+
+        let without_first_light = APIResponseDay { first_light: None, ..day };
+        let error = Timer::map_twilight(&without_first_light, Twilight::FirstLight, "Natural").unwrap_err();
+        assert_eq!(error.0, StatusCode::BAD_REQUEST);
+        assert!(error.1.starts_with("Natural first light is missing"));
+        assert!(Timer::map_twilight(&without_first_light, Twilight::Sunrise, "Natural").is_ok());
+    }
+
+    #[test]
+    fn daily() {
+        let day_timer = day::Timer::new(Time::new(8, 0), Time::new(20, 0));
+        assert!(Timer::daily(&day_timer).day_timers().iter().all(|timer| *timer == day_timer));
+    }
+
+    #[test]
+    fn monthly_anchors() {
+        let timer = Timer::monthly(&month_timers()).unwrap();
+        let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        assert_eq!(*timer.for_date(date(1, 15)), month_timers()[0]);
+        assert_eq!(*timer.for_date(date(2, 15)), month_timers()[1]);
+        assert_eq!(*timer.for_date(date(12, 15)), month_timers()[11]);
+    }
+
+    #[test]
+    fn monthly_interpolated() {
+        let timer = Timer::monthly(&month_timers()).unwrap();
+        // june 15th to july 15th is 30 days
+        let date = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+        assert_eq!(*timer.for_date(date), day::Timer::new(Time::new(8, 30), Time::new(19, 30)));
+        // december 15th to january 15th wraps around the end of the year
+        let date = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        assert_eq!(*timer.for_date(date), day::Timer::new(Time::new(8, 29), Time::new(19, 31)));
+    }
+
+    #[test]
+    fn monthly_natural_factors() {
+        let factors = Timer::monthly_natural_factors(&std::array::from_fn(|month| if month % 2 == 0 { 0. } else { 1. }));
+        let index = |month, day| Timer::index(NaiveDate::from_ymd_opt(2025, month, day).unwrap());
+        assert!(factors[index(1, 15)].abs() < f32::EPSILON);
+        assert!((factors[index(6, 15)] - 1.).abs() < f32::EPSILON);
+        // june 15th to july 15th is 30 days
+        assert!((factors[index(6, 30)] - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn monthly_invalid() {
+        let mut timers = month_timers();
+        timers[3] = day::Timer::from_intervals(vec![
+            day::Interval::new(Time::new(8, 0), Time::new(12, 0)),
+            day::Interval::new(Time::new(14, 0), Time::new(20, 0)),
+        ]).unwrap();
+        assert!(Timer::monthly(&timers).is_err());
+    }
+
+    #[test]
+    fn interpolate_over_midnight() {
+        assert_eq!(Timer::interpolate(Time::new(23, 0), Time::new(1, 0), 0.5), Time::new(0, 0));
+        assert_eq!(Timer::interpolate(Time::new(1, 0), Time::new(23, 0), 0.25), Time::new(0, 30));
+    }
+
     fn index_test(year: i32, month: u32, day: u32, index: usize) {
-        use chrono::TimeZone;
-        let time = chrono_tz::CET.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap();
-        assert_eq!(Timer::index(time), index);
+        let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        assert_eq!(Timer::index(date), index);
     }
 
     #[test]